@@ -18,21 +18,42 @@
 //! - `Editor`: 主控制器，协调各子系统
 //! - `Location`: 光标位置状态
 //! - 事件处理器：将原始事件转换为编辑器操作
+//! - `TerminalBackend`: 终端后端, 可替换为 `VirtualTerminal` 进行无终端测试
 
+mod args;
+mod config;
 mod editorcommand;
+mod frame;
+mod stdinreader;
+mod style;
 mod terminal;
+mod theme;
 mod view;
 
-use crossterm::event::{read, Event};
-use editorcommand::EditorCommand;
+use config::Config;
+use crossterm::event::Event;
+use editorcommand::{resolve_legacy_key, EditorCommand, Prefix};
+use frame::Frame;
+use stdinreader::{StdinReader, StdinStatus};
 
+pub use args::{Action, Args, FileArg};
 use std::{
-    env,
-    io::Error,
+    fs::OpenOptions,
+    io::{self, Error, ErrorKind, IsTerminal},
     panic::{set_hook, take_hook},
+    time::{Duration, Instant},
 };
-use terminal::{Position, Terminal};
+pub use style::{Color, ColorLevel, Style};
+pub use terminal::{CursorShape, Position, Size, Terminal, TerminalBackend, VirtualTerminal};
+use theme::{Theme, DEFAULT_THEME};
 use view::View;
+
+/// 读取管道输入、跟随文件或查找文件时等待按键的最长时间, 超时后追加新到达的内容
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 写入交换文件、检查文件是否被外部修改的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(2);
+
 /// 编辑器主控制器
 ///
 /// ## 职责划分
@@ -43,8 +64,26 @@ use view::View;
 pub struct Editor {
     /// 退出标志，控制主循环终止
     should_quit: bool,
+    /// 等待后续按键的前缀命令
+    pending_prefix: Option<Prefix>,
     /// 视图控制器实例
     view: View,
+    /// 终端后端
+    terminal: Box<dyn TerminalBackend>,
+    /// 上一次刷新到终端的帧(前缓冲), `None` 表示需要完整重绘
+    last_frame: Option<Frame>,
+    /// 配色主题
+    theme: Theme,
+    /// 已设置到终端的光标形状, `None` 表示终端默认形状
+    cursor_shape: Option<CursorShape>,
+    /// 正在读取的标准输入
+    stdin: Option<StdinReader>,
+    /// 分页模式, 按键按 `less` 的习惯解析
+    pager: bool,
+    /// 文件被外部修改且缓冲区未修改时自动重新加载
+    auto_reload: bool,
+    /// 上次写入交换文件、检查外部修改的时间
+    last_tick: Instant,
 }
 
 impl Editor {
@@ -52,63 +91,182 @@ impl Editor {
     ///
     /// ## 初始化流程
     /// 1. 添加自定义panic hook
-    /// 2. Terminal初始化
-    /// 3. 构建view
-    /// 4. 解析命令行参数,加载文件
+    /// 2. 没有指定文件且标准输入不是终端(管道输入)时, 读取标准输入
+    /// 3. 使用 `crossterm` 终端后端构建编辑器
+    ///
+    /// # 参数
+    /// - `args`: 已解析的命令行参数
     ///
     /// ## 设计思想
     /// 把所有无法处理且需要panic的错误都移动到了new中
     /// 对于其他所有情况,我们应当容忍错误,不要让程序崩溃,这里选择设置run禁止向上传播错误
-    pub fn new() -> Result<Self, Error> {
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果指定的配置文件无法读取或终端初始化失败
+    pub fn new(args: &Args) -> Result<Self, Error> {
         let cur_hook = take_hook();
         // 使用move将所有权转移到闭包中,防止cur_hook在new后被drop
         set_hook(Box::new(move |panic_info| {
             // println!("Custom panic hook"); // test done, No problem
-            let _ = Terminal::terminate();
+            // 恢复raw mode、备用Screen和光标形状, 使panic信息正常显示
+            let _ = Terminal.terminate();
             cur_hook(panic_info);
         }));
-        Terminal::initialize()?;
-        let mut view = View::default();
-        let args: Vec<String> = env::args().collect();
-        if let Some(filename) = args.get(1) {
-            view.load_file(filename);
-        } else {
+        if args.files.is_empty() && !io::stdin().is_terminal() {
+            let mut args = args.clone();
+            args.files.push(FileArg {
+                path: "-".to_string(),
+                ..FileArg::default()
+            });
+            return Self::with_backend(Box::new(Terminal), &args);
+        }
+        Self::with_backend(Box::new(Terminal), args)
+    }
+
+    /// 使用指定终端后端构建编辑器
+    ///
+    /// ## 初始化流程
+    /// 1. 加载配置; `args` 指定了配置、状态和缓存目录时不会读写用户主目录
+    /// 2. Terminal初始化
+    /// 3. 构建view
+    /// 4. 每个文件在各自的缓冲区中打开并定位, 显示第一个; 文件名 `-` 表示在后台读取标准输入
+    ///
+    /// # 参数
+    /// - `terminal`: 终端后端, 测试时可传入 [`VirtualTerminal`]
+    /// - `args`: 已解析的命令行参数
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果指定的配置文件无法读取或终端初始化失败,
+    /// 此时终端已恢复原状
+    pub fn with_backend(
+        mut terminal: Box<dyn TerminalBackend>,
+        args: &Args,
+    ) -> Result<Self, Error> {
+        // 在进入 raw mode 之前读取配置, 错误可以正常显示
+        let (config, warnings) = Config::load(args.config.as_deref(), args.config_dir.as_deref())
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        if let Err(err) = terminal.initialize() {
+            let _ = terminal.terminate();
+            return Err(err);
+        }
+        let mut view = View::new(terminal.size().unwrap_or_default());
+        if terminal.keyboard_enhancement() {
+            view.log_event("INFO", "Keyboard enhancement enabled.");
+        }
+        view.set_scroll_off(config.scroll_off);
+        view.set_line_numbers(config.line_numbers);
+        view.set_log_key_release(config.log_key_release);
+        view.set_info_panel(config.info_height, config.info_position, &config.info_mute);
+        for warning in warnings {
+            view.log_event("INFO", &warning);
+        }
+        view.load_file_states(args.state_dir.as_deref());
+        view.set_cache_dir(args.cache_dir.clone());
+        let mut theme = Theme::load(&config.theme, config.dir.as_deref()).unwrap_or_else(|err| {
+            view.log_event("INFO", &err);
+            Theme::load(DEFAULT_THEME, None).unwrap_or_default()
+        });
+        // 命令行指定的颜色能力优先于配置文件
+        theme.quantize(ColorLevel::resolve(args.colors, config.colors));
+        if let Some(path) = &args.log_file {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => view.set_log_file(file),
+                Err(err) => view.log_event("INFO", &format!("Cannot open log file: {err}")),
+            }
+        }
+        if args.files.is_empty() {
             view.log_event("INFO", "No file opened.");
         }
+        view.set_read_only(args.read_only);
+        let mut stdin = None;
+        for file in &args.files {
+            if file.path == "-" {
+                if stdin.is_some() {
+                    view.log_event("INFO", "stdin can only be read once.");
+                } else {
+                    view.open_stdin();
+                    stdin = Some(StdinReader::spawn());
+                }
+                continue;
+            }
+            if !view.load_file(&file.path) {
+                continue;
+            }
+            if let Some(line) = file.line {
+                view.go_to(line, file.column.unwrap_or(1));
+            }
+        }
+        view.switch_buffer(0);
         Ok(Self {
             should_quit: false,
+            pending_prefix: None,
             view,
+            terminal,
+            last_frame: None,
+            theme,
+            cursor_shape: None,
+            stdin,
+            pager: args.pager,
+            auto_reload: config.auto_reload,
+            last_tick: Instant::now(),
         })
     }
+
     /// 启动编辑器主循环
     ///
     /// ## 执行序列
     /// 1. 启动REPL事件循环
+    /// 2. 正常退出后保存各文件的视图状态, 删除交换文件
     pub fn run(&mut self) {
         self.repl();
+        self.view.save_file_states();
+        self.view.remove_swap_files();
     }
 
     /// 主事件循环 (REPL模式)
     ///
     /// ## 核心流程
-    /// 1. 刷新屏幕
-    /// 2. 读取输入事件
-    /// 3. 处理事件
-    /// 4. 循环直到退出标志置位
+    /// 1. 追加已到达的管道输入、跟随的文件新增的内容和查找到的文件, 每隔 [`TICK_INTERVAL`] 写入交换文件并检查外部修改
+    /// 2. 刷新屏幕
+    /// 3. 读取输入事件(读取管道输入、跟随文件、查找文件或等待文件预览时最多等待 [`POLL_INTERVAL`], 否则等到下次写入交换文件)
+    /// 4. 处理事件
+    /// 5. 循环直到退出标志置位
     ///
     /// ## 错误处理
-    /// 1. debug模式下, 单次read event失败, 直接panic, 程序退出
-    /// 2. release模式下, 容忍单次read event失败, 程序不退出, 忽略错误, 继续循环, 尝试刷新屏幕(保持监听,如果事件到了, 仍正常处理)
+    /// 1. 输入耗尽(如虚拟终端的事件队列为空)时结束循环
+    /// 2. debug模式下, 单次read event失败, 直接panic, 程序退出
+    /// 3. release模式下, 容忍单次read event失败, 程序不退出, 忽略错误, 继续循环, 尝试刷新屏幕(保持监听,如果事件到了, 仍正常处理)
     fn repl(&mut self) {
         loop {
+            let pending = self.read_stdin();
+            self.view.follow_file();
+            self.view.poll_finder();
+            if self.last_tick.elapsed() >= TICK_INTERVAL {
+                self.tick();
+            }
             self.refresh_screen();
             if self.should_quit {
                 break;
             }
-            match read() {
+            // 不能一直阻塞在按键上, 超时后回到循环开头追加新内容或检查外部修改
+            let timeout = if pending {
+                Duration::ZERO
+            } else if self.stdin.is_some()
+                || self.view.is_following()
+                || self.view.is_finder_loading()
+            {
+                POLL_INTERVAL
+            } else {
+                TICK_INTERVAL.saturating_sub(self.last_tick.elapsed())
+            };
+            if !self.terminal.poll_event(timeout).unwrap_or(true) {
+                continue;
+            }
+            match self.terminal.read_event() {
                 Ok(event) => {
                     self.evaluate_event(event);
                 }
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
                 Err(err) => {
                     #[cfg(debug_assertions)]
                     {
@@ -123,54 +281,195 @@ impl Editor {
         }
     }
 
+    /// 定期任务: 把已修改的缓冲区写入交换文件, 检查当前缓冲区的文件是否被外部修改
+    fn tick(&mut self) {
+        self.last_tick = Instant::now();
+        self.view.write_swap_files();
+        self.view.check_disk(self.auto_reload);
+    }
+
+    /// 把已到达的管道输入追加到缓冲区, 输入结束或缓冲区被关闭后停止读取
+    ///
+    /// # 返回
+    /// 还有已到达的行没有取出时返回 `true`
+    fn read_stdin(&mut self) -> bool {
+        let Some(stdin) = &self.stdin else {
+            return false;
+        };
+        let mut lines = Vec::new();
+        let status = stdin.read_lines(&mut lines);
+        if !lines.is_empty() && !self.view.append_stdin(lines) {
+            self.stdin = None;
+            return false;
+        }
+        match status {
+            StdinStatus::Open => {}
+            StdinStatus::Pending => return true,
+            StdinStatus::Closed => {
+                self.view.finish_stdin(None);
+                self.stdin = None;
+            }
+            StdinStatus::Failed(err) => {
+                self.view.finish_stdin(Some(&err));
+                self.stdin = None;
+            }
+        }
+        false
+    }
+
     /// 事件评估与路由
     ///
     /// ## 处理策略
+    /// 0. 未启用键盘增强协议时, 先统一有歧义的按键(`Ctrl+h` 视为 `Backspace`)
     /// 1. 记录所有按键事件
     /// 2. 特殊组合键触发状态变更
     /// 3. 导航键更新光标位置
     /// 4. 窗口尺寸变化通知视图
+    /// 5. 前缀命令(如 `m`)等待下一个按键组合成完整命令
+    /// 6. 日志查看器打开时, 按键只用于浏览日志
+    /// 7. 缓冲区选择器打开时, 按键只用于选择和关闭缓冲区
     fn evaluate_event(&mut self, event: Event) {
-        let command = EditorCommand::try_from(event);
+        let event = if self.terminal.keyboard_enhancement() {
+            event
+        } else {
+            resolve_legacy_key(event)
+        };
+        if self.view.is_log_viewer_open() {
+            self.execute_command(EditorCommand::try_from_log_viewer(event));
+            return;
+        }
+        if self.view.is_buffer_picker_open() {
+            self.execute_command(EditorCommand::try_from_picker(event));
+            return;
+        }
+        if self.view.is_finder_open() {
+            self.execute_command(EditorCommand::try_from_finder(event));
+            return;
+        }
+        if self.view.is_explorer_prompt_open() {
+            self.execute_command(EditorCommand::try_from_explorer_prompt(event));
+            return;
+        }
+        if self.view.is_swap_prompt_open() {
+            self.execute_command(EditorCommand::try_from_swap_prompt(event));
+            return;
+        }
+        if self.view.is_disk_prompt_open() {
+            self.execute_command(EditorCommand::try_from_disk_prompt(event));
+            return;
+        }
+        let prefixed = self
+            .pending_prefix
+            .and_then(|prefix| EditorCommand::try_from_prefixed(prefix, &event));
+        let command = match prefixed {
+            Some(command) => {
+                self.pending_prefix = None;
+                command
+            }
+            None if self.view.is_explorer_active() => EditorCommand::try_from_explorer(event),
+            None if self.pager => EditorCommand::try_from_pager(event),
+            None => EditorCommand::try_from(event),
+        };
+        self.execute_command(command);
+    }
+
+    /// 执行解析得到的命令
+    fn execute_command(&mut self, command: Result<EditorCommand, String>) {
         match command {
             Ok(EditorCommand::Quit) => self.should_quit = true,
+            Ok(EditorCommand::Suspend) => self.suspend(),
+            Ok(EditorCommand::Prefix(prefix)) => self.pending_prefix = Some(prefix),
+            Ok(EditorCommand::FocusGained) => self.view.check_disk(self.auto_reload),
             Ok(command) => {
                 self.view.handle_command(command);
             }
             Err(err) => {
-                let info = format!("Command {err} Not Supported, Press <Ctrl+h> for help");
+                let info = format!("Command {err} Not Supported, Press <F1> for help");
                 self.view.log_event("NSUP", &info);
             }
         }
     }
 
+    /// 挂起到后台, 恢复后重新获取终端尺寸并完整重绘
+    fn suspend(&mut self) {
+        // 挂起的进程可能不会再恢复, 先保存未写入的修改
+        self.view.write_swap_files();
+        if let Err(err) = self.terminal.suspend() {
+            self.view
+                .log_event("INFO", &format!("Cannot suspend: {err}"));
+            return;
+        }
+        // 挂起期间终端内容和尺寸都可能改变, 光标形状已恢复为默认
+        self.last_frame = None;
+        self.cursor_shape = None;
+        let size = self.terminal.size().unwrap_or_default();
+        if size != self.view.size() {
+            self.view.handle_command(EditorCommand::Resize(size));
+        }
+        self.view.log_event("INFO", "Resumed.");
+    }
+
+    /// 当前状态对应的光标形状
+    ///
+    /// - 等待前缀命令的后续按键、浏览日志、选择缓冲区、选择如何处理外部修改或交换文件、输入目录操作时: 下划线
+    /// - 在文件查找器中输入查询时: 竖线
+    /// - 其他情况: 方块
+    fn current_cursor_shape(&self) -> CursorShape {
+        if self.view.is_finder_open() {
+            CursorShape::Bar
+        } else if self.pending_prefix.is_some()
+            || self.view.is_log_viewer_open()
+            || self.view.is_buffer_picker_open()
+            || self.view.is_disk_prompt_open()
+            || self.view.is_swap_prompt_open()
+            || self.view.is_explorer_prompt_open()
+        {
+            CursorShape::Underline
+        } else {
+            CursorShape::Block
+        }
+    }
+
     /// 刷新屏幕内容
     ///
     /// ## 双状态渲染
-    /// 1. 正常状态：更新视图内容
+    /// 1. 正常状态：整帧绘制到后缓冲, 与前缓冲比较后只输出变化的单元格
     /// 2. 退出状态：显示告别信息
     fn refresh_screen(&mut self) {
         // explicitly ignore the Result value & Error
-        let _ = Terminal::hide_cursor();
+        let _ = self.terminal.hide_cursor();
 
         if self.should_quit {
             // 退出状态渲染
-            let _ = Terminal::clear_screen();
-            let _ = Terminal::move_cursor_to(Position { x: 0, y: 0 });
-            let _ = Terminal::print("Goodbye. <rim> user.\r\n");
+            let _ = self.terminal.clear_screen();
+            let _ = self.terminal.move_cursor_to(Position { x: 0, y: 0 });
+            let _ = self.terminal.print("Goodbye. <rim> user.\r\n");
+            self.last_frame = None;
         } else {
             // 正常状态渲染
-            self.view.render();
-            let _ = Terminal::move_cursor_to(self.view.get_cursor_position());
+            let mut frame = Frame::new(self.view.size());
+            self.view.render(&mut frame, &self.theme);
+            // 刷新失败时终端内容未知, 下次完整重绘
+            self.last_frame = frame
+                .flush(self.terminal.as_mut(), self.last_frame.as_ref())
+                .ok()
+                .map(|()| frame);
+            let _ = self
+                .terminal
+                .move_cursor_to(self.view.get_cursor_position());
+            let shape = self.current_cursor_shape();
+            if self.cursor_shape != Some(shape) && self.terminal.set_cursor_shape(shape).is_ok() {
+                self.cursor_shape = Some(shape);
+            }
         }
 
-        let _ = Terminal::show_cursor();
-        let _ = Terminal::execute();
+        let _ = self.terminal.show_cursor();
+        let _ = self.terminal.execute();
     }
 }
 
 impl Drop for Editor {
     fn drop(&mut self) {
-        let _ = Terminal::terminate(); // must ignore error, in case of Double Panic
+        let _ = self.terminal.terminate(); // must ignore error, in case of Double Panic
     }
 }
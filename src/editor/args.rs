@@ -0,0 +1,368 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 21:03:15
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 21:03:15
+ * @FilePath: \rim\src\editor\args.rs
+ * @Description: 命令行参数解析
+ */
+//! 命令行参数模块
+//!
+//! 在终端进入 raw mode 之前解析参数, 帮助、版本和参数错误都可以直接打印到终端
+//!
+//! ```text
+//! rim-viewer [OPTIONS] [+LINE] [FILE[:LINE[:COL]]]...
+//! ```
+//!
+//! - `+LINE` 指定下一个文件打开时的行号, 单独的 `+` 表示最后一行
+//! - `FILE:LINE:COL` 只在该路径本身不存在时才按位置解析, 兼容编译器和 `grep -n` 的输出
+//! - `--` 之后的参数都作为文件名
+//! - 文件名 `-` 表示读取标准输入
+
+use super::style::ColorLevel;
+use std::path::{Path, PathBuf};
+
+const NAME: &str = env!("CARGO_PKG_NAME");
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 命令行要求的操作
+pub enum Action {
+    /// 启动编辑器
+    Open(Args),
+    /// 打印帮助后退出
+    Help,
+    /// 打印版本后退出
+    Version,
+}
+
+/// 要打开的文件及初始光标位置
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileArg {
+    /// 文件路径
+    pub path: String,
+    /// 初始行号(从 1 开始), `usize::MAX` 表示最后一行
+    pub line: Option<usize>,
+    /// 初始列号(从 1 开始)
+    pub column: Option<usize>,
+}
+
+/// 启动编辑器的参数
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    /// 依次打开的文件, 每个文件一个缓冲区
+    pub files: Vec<FileArg>,
+    /// 以只读方式打开所有文件
+    pub read_only: bool,
+    /// 指定的配置文件, `None` 使用默认路径
+    pub config: Option<PathBuf>,
+    /// 同步追加事件日志的文件
+    pub log_file: Option<PathBuf>,
+    /// 分页模式: 类似 `less` 的按键, 所有缓冲区只读
+    pub pager: bool,
+    /// 强制使用的颜色能力, 优先于配置文件; `None` 使用配置文件的设置
+    pub colors: Option<ColorLevel>,
+    /// 配置目录(配置文件和用户主题), `None` 使用默认位置; 没有对应的命令行选项,
+    /// 供测试等场景避免读写用户主目录, 下同
+    pub config_dir: Option<PathBuf>,
+    /// 状态目录(各文件的视图状态), `None` 使用默认位置
+    pub state_dir: Option<PathBuf>,
+    /// 缓存目录(交换文件), `None` 使用默认位置
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Args {
+    /// 解析命令行参数
+    ///
+    /// # 参数
+    /// - `args`: 命令行参数(不含程序名)
+    ///
+    /// # 错误
+    /// 未知选项、缺少选项值、选项值或行号不合法时返回错误描述
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Action, String> {
+        let mut parsed = Self::default();
+        let mut line = None;
+        let mut only_files = false;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if only_files || arg == "-" || !arg.starts_with(['-', '+']) {
+                let mut file = parse_file(&arg);
+                if line.is_some() {
+                    file.line = line.take();
+                    file.column = None;
+                }
+                parsed.files.push(file);
+                continue;
+            }
+            if let Some(number) = arg.strip_prefix('+') {
+                line = Some(parse_line(number)?);
+                continue;
+            }
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |option: &str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("option '{option}' requires a value"))
+            };
+            match name {
+                "--" => only_files = true,
+                "-h" | "--help" => return Ok(Action::Help),
+                "-V" | "--version" => return Ok(Action::Version),
+                "-R" | "--readonly" => parsed.read_only = true,
+                "--pager" => {
+                    parsed.pager = true;
+                    parsed.read_only = true;
+                }
+                "--config" => parsed.config = Some(PathBuf::from(value(name)?)),
+                "--log-file" => parsed.log_file = Some(PathBuf::from(value(name)?)),
+                "--colors" => parsed.colors = Some(parse_colors(&value(name)?)?),
+                _ => return Err(format!("unknown option '{arg}'")),
+            }
+        }
+        if line.is_some() {
+            return Err("'+LINE' must be followed by a file".to_string());
+        }
+        Ok(Action::Open(parsed))
+    }
+
+    /// 帮助信息
+    #[must_use]
+    pub fn usage() -> String {
+        format!(
+            "{NAME} {VERSION}
+A terminal-based text viewer
+
+Usage: {NAME} [OPTIONS] [+LINE] [FILE[:LINE[:COL]]]...
+       <command> | {NAME} [-]
+
+A FILE that is a directory opens a file browser.
+
+Options:
+  -h, --help             Print help and exit
+  -V, --version          Print version and exit
+  -R, --readonly         Open all files read-only
+      --pager            Use less-style keys (space, b, g, G, q) and open files read-only
+      --config <PATH>    Read the config from PATH instead of the default location
+      --log-file <PATH>  Append the event log to PATH
+      --colors <LEVEL>   Use none, 16, 256 or truecolor colors instead of the configured level
+  +LINE                  Open the next file at LINE ('+' alone for the last line)
+  --                     Treat all following arguments as files"
+        )
+    }
+
+    /// 版本信息
+    #[must_use]
+    pub fn version() -> String {
+        format!("{NAME} {VERSION}")
+    }
+}
+
+/// 解析 `+LINE` 中的行号, 空字符串表示最后一行
+fn parse_line(number: &str) -> Result<usize, String> {
+    if number.is_empty() {
+        return Ok(usize::MAX);
+    }
+    number
+        .parse()
+        .map_err(|_| format!("invalid line number '+{number}'"))
+}
+
+/// 解析 `--colors` 的值, 不接受 `auto`(不指定该选项即为配置文件的设置)
+fn parse_colors(value: &str) -> Result<ColorLevel, String> {
+    ColorLevel::parse(value).ok().flatten().ok_or_else(|| {
+        format!("invalid color level '{value}', expected none / 16 / 256 / truecolor")
+    })
+}
+
+/// 解析文件参数
+///
+/// 路径本身不存在时才尝试去掉 `:LINE` 或 `:LINE:COL` 后缀(允许末尾多一个 `:`)
+fn parse_file(arg: &str) -> FileArg {
+    let literal = FileArg {
+        path: arg.to_string(),
+        ..FileArg::default()
+    };
+    if Path::new(arg).exists() {
+        return literal;
+    }
+    let trimmed = arg.strip_suffix(':').unwrap_or(arg);
+    let mut numbers = Vec::new();
+    let mut path = trimmed;
+    while numbers.len() < 2 {
+        let Some((rest, number)) = path.rsplit_once(':') else {
+            break;
+        };
+        let Ok(number) = number.parse::<usize>() else {
+            break;
+        };
+        numbers.insert(0, number);
+        path = rest;
+    }
+    if numbers.is_empty() || path.is_empty() {
+        return literal;
+    }
+    FileArg {
+        path: path.to_string(),
+        line: numbers.first().copied(),
+        column: numbers.get(1).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 解析参数, 要求结果为启动编辑器
+    fn open(args: &[&str]) -> Args {
+        match Args::parse(args.iter().map(|arg| (*arg).to_string())) {
+            Ok(Action::Open(args)) => args,
+            Ok(_) => panic!("{args:?} did not open the editor"),
+            Err(err) => panic!("{args:?} failed: {err}"),
+        }
+    }
+
+    fn file(path: &str, line: Option<usize>, column: Option<usize>) -> FileArg {
+        FileArg {
+            path: path.to_string(),
+            line,
+            column,
+        }
+    }
+
+    #[test]
+    fn plus_line_applies_to_next_file() {
+        let args = open(&["+12", "missing-a.txt", "missing-b.txt"]);
+        assert_eq!(
+            args.files,
+            [
+                file("missing-a.txt", Some(12), None),
+                file("missing-b.txt", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_plus_means_last_line() {
+        let args = open(&["+", "missing.txt"]);
+        assert_eq!(args.files, [file("missing.txt", Some(usize::MAX), None)]);
+    }
+
+    #[test]
+    fn plus_line_overrides_file_position() {
+        let args = open(&["+3", "missing.txt:7:2"]);
+        assert_eq!(args.files, [file("missing.txt", Some(3), None)]);
+    }
+
+    #[test]
+    fn plus_line_errors() {
+        assert!(Args::parse(["+x".to_string(), "missing.txt".to_string()]).is_err());
+        assert!(Args::parse(["+5".to_string()]).is_err());
+    }
+
+    #[test]
+    fn file_line_and_column() {
+        let args = open(&["missing.rs:12", "missing.rs:12:5", "missing.rs:12:5:"]);
+        assert_eq!(
+            args.files,
+            [
+                file("missing.rs", Some(12), None),
+                file("missing.rs", Some(12), Some(5)),
+                file("missing.rs", Some(12), Some(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn file_suffix_that_is_not_a_position() {
+        let args = open(&["missing.rs:abc", ":12", "a:b:1:2:3"]);
+        assert_eq!(
+            args.files,
+            [
+                file("missing.rs:abc", None, None),
+                file(":12", None, None),
+                file("a:b:1", Some(2), Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_path_is_not_split() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let path = format!("{dir}/Cargo.toml");
+        let args = open(&[&path]);
+        assert_eq!(args.files, [file(&path, None, None)]);
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        let args = open(&["-R", "--", "--pager", "+3", "-"]);
+        assert!(args.read_only);
+        assert!(!args.pager);
+        assert_eq!(
+            args.files,
+            [
+                file("--pager", None, None),
+                file("+3", None, None),
+                file("-", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_dash_is_stdin() {
+        let args = open(&["-"]);
+        assert_eq!(args.files, [file("-", None, None)]);
+    }
+
+    #[test]
+    fn option_values() {
+        let args = open(&[
+            "--config=a.conf",
+            "--log-file",
+            "rim.log",
+            "--colors",
+            "256",
+        ]);
+        assert_eq!(args.config, Some(PathBuf::from("a.conf")));
+        assert_eq!(args.log_file, Some(PathBuf::from("rim.log")));
+        assert_eq!(args.colors, Some(ColorLevel::Ansi256));
+        assert!(args.files.is_empty());
+    }
+
+    #[test]
+    fn option_value_errors() {
+        assert!(Args::parse(["--config".to_string()]).is_err());
+        assert!(Args::parse(["--colors=auto".to_string()]).is_err());
+    }
+
+    #[test]
+    fn pager_implies_read_only() {
+        let args = open(&["--pager"]);
+        assert!(args.pager);
+        assert!(args.read_only);
+    }
+
+    #[test]
+    fn help_and_version() {
+        assert!(matches!(
+            Args::parse(["missing.txt".to_string(), "--help".to_string()]),
+            Ok(Action::Help)
+        ));
+        assert!(matches!(
+            Args::parse(["-V".to_string()]),
+            Ok(Action::Version)
+        ));
+    }
+
+    #[test]
+    fn unknown_option() {
+        let Err(err) = Args::parse(["--frobnicate".to_string()]) else {
+            panic!("unknown option accepted");
+        };
+        assert_eq!(err, "unknown option '--frobnicate'");
+        assert!(Args::parse(["-x=1".to_string()]).is_err());
+    }
+}
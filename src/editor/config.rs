@@ -0,0 +1,198 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 11:02:47
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 11:02:47
+ * @FilePath: \rim\src\editor\config.rs
+ * @Description: 用户配置
+ */
+//! 用户配置模块
+//!
+//! 配置文件为简单的 `key = value` 格式, `#` 开头的行为注释:
+//!
+//! ```text
+//! # 光标与可见区域上下边缘保持的最小行数
+//! scroll_off = 3
+//! # 主题名(内置 dark / light)或主题文件路径
+//! theme = dark
+//! # 颜色能力: auto / truecolor / 256 / 16 / none
+//! colors = auto
+//! # 是否显示行号
+//! line_numbers = false
+//! # 信息面板行数
+//! info_height = 5
+//! # 信息面板位置: top / bottom / hidden
+//! info_position = top
+//! # 信息面板中不显示的事件标签, 逗号分隔
+//! info_mute = MOVE, KEY
+//! # 文件被外部修改且缓冲区未修改时自动重新加载
+//! auto_reload = false
+//! # 在日志中记录按键松开事件(需要终端支持键盘增强协议)
+//! log_key_release = false
+//! ```
+//!
+//! 默认路径(可以用 `--config <PATH>` 指定其他文件, 或通过 [`super::Args::config_dir`] 指定配置目录):
+//! - `$XDG_CONFIG_HOME/rim/config`
+//! - `$HOME/.config/rim/config`
+//! - `%APPDATA%\rim\config` (Windows)
+
+use super::style::ColorLevel;
+use super::theme::DEFAULT_THEME;
+use super::view::{InfoPosition, DEFAULT_INFO_HEIGHT};
+use std::env;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// 默认的滚动边距
+const DEFAULT_SCROLL_OFF: usize = 3;
+
+/// 编辑器配置
+pub struct Config {
+    /// 光标与可见区域上下边缘保持的最小行数
+    pub scroll_off: usize,
+    /// 主题名或主题文件路径
+    pub theme: String,
+    /// 强制使用的颜色能力, `None` 表示自动检测
+    pub colors: Option<ColorLevel>,
+    /// 是否显示行号栏
+    pub line_numbers: bool,
+    /// 信息面板行数
+    pub info_height: usize,
+    /// 信息面板位置
+    pub info_position: InfoPosition,
+    /// 信息面板中不显示的事件标签
+    pub info_mute: Vec<String>,
+    /// 文件被外部修改且缓冲区未修改时自动重新加载, 否则提示
+    pub auto_reload: bool,
+    /// 在日志中记录按键松开事件
+    pub log_key_release: bool,
+    /// 配置目录, 用户主题位于其中的 `themes` 目录; 找不到时为 `None`
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scroll_off: DEFAULT_SCROLL_OFF,
+            theme: String::from(DEFAULT_THEME),
+            colors: None,
+            line_numbers: false,
+            info_height: DEFAULT_INFO_HEIGHT,
+            info_position: InfoPosition::default(),
+            info_mute: Vec::new(),
+            auto_reload: false,
+            log_key_release: false,
+            dir: None,
+        }
+    }
+}
+
+impl Config {
+    /// 加载配置
+    ///
+    /// # 参数
+    /// - `path`: 指定的配置文件, `None` 时从配置目录加载
+    /// - `dir`: 指定的配置目录, `None` 时使用默认位置
+    ///
+    /// # 返回
+    /// 配置和解析过程中的警告信息; 配置目录中的配置文件不存在时使用默认配置
+    ///
+    /// # 错误
+    /// 指定的配置文件无法读取时返回错误描述
+    pub fn load(path: Option<&Path>, dir: Option<&Path>) -> Result<(Self, Vec<String>), String> {
+        let mut config = Self {
+            dir: dir.map(Path::to_path_buf).or_else(Self::config_dir),
+            ..Self::default()
+        };
+        let contents = if let Some(path) = path {
+            read_to_string(path)
+                .map_err(|err| format!("cannot read config {}: {err}", path.display()))?
+        } else {
+            let Some(path) = config.dir.as_ref().map(|dir| dir.join("config")) else {
+                return Ok((config, Vec::new()));
+            };
+            let Ok(contents) = read_to_string(&path) else {
+                return Ok((config, Vec::new()));
+            };
+            contents
+        };
+        let warnings = config.parse(&contents);
+        Ok((config, warnings))
+    }
+
+    /// 默认的配置目录, 配置文件和用户主题都位于其中
+    fn config_dir() -> Option<PathBuf> {
+        if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            return Some(PathBuf::from(dir).join("rim"));
+        }
+        if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty()) {
+            return Some(PathBuf::from(home).join(".config").join("rim"));
+        }
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("rim"))
+    }
+
+    /// 解析配置文件内容
+    ///
+    /// 无法识别的行不会中断解析, 而是作为警告返回
+    fn parse(&mut self, contents: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let result = match line.split_once('=') {
+                Some((key, value)) => self.set(key.trim(), value.trim()),
+                None => Err(format!("expected `key = value`, found {line:?}")),
+            };
+            if let Err(err) = result {
+                warnings.push(format!("config line {}: {err}", index + 1));
+            }
+        }
+        warnings
+    }
+
+    /// 设置单个配置项
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "scroll_off" => {
+                self.scroll_off = value
+                    .parse()
+                    .map_err(|_| format!("invalid scroll_off {value:?}"))?;
+            }
+            "theme" => value.clone_into(&mut self.theme),
+            "colors" => self.colors = ColorLevel::parse(value)?,
+            "line_numbers" => {
+                self.line_numbers = value.parse().map_err(|_| {
+                    format!("invalid line_numbers {value:?}, expected true / false")
+                })?;
+            }
+            "info_height" => {
+                self.info_height = value
+                    .parse()
+                    .map_err(|_| format!("invalid info_height {value:?}"))?;
+            }
+            "info_position" => self.info_position = InfoPosition::parse(value)?,
+            "info_mute" => {
+                self.info_mute = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            "auto_reload" => {
+                self.auto_reload = value
+                    .parse()
+                    .map_err(|_| format!("invalid auto_reload {value:?}, expected true / false"))?;
+            }
+            "log_key_release" => {
+                self.log_key_release = value.parse().map_err(|_| {
+                    format!("invalid log_key_release {value:?}, expected true / false")
+                })?;
+            }
+            _ => return Err(format!("unknown option {key:?}")),
+        }
+        Ok(())
+    }
+}
@@ -12,31 +12,273 @@ pub enum Direction {
     PageDown,
     Home,
     End,
+    /// 文件首行
+    FileStart,
+    /// 文件末行
+    FileEnd,
+}
+
+/// 视图滚动方式(不移动光标)
+#[derive(Copy, Clone, Debug)]
+pub enum Scroll {
+    LineUp,
+    LineDown,
+    HalfPageUp,
+    HalfPageDown,
+}
+
+/// 光标行在视图中重新定位的位置
+#[derive(Copy, Clone, Debug)]
+pub enum Recenter {
+    /// `zt`
+    Top,
+    /// `zz`
+    Middle,
+    /// `zb`
+    Bottom,
+}
+
+/// 需要等待下一个按键才能完成的前缀命令
+#[derive(Copy, Clone, Debug)]
+pub enum Prefix {
+    /// `m`: 设置标记
+    SetMark,
+    /// `'`: 跳转到标记
+    JumpToMark,
+    /// `z`: 视图定位
+    View,
+    /// `]`: 下一个
+    Next,
+    /// `[`: 上一个
+    Previous,
+    /// `Ctrl+w`: 窗口操作
+    Window,
+}
+
+/// 窗口操作
+#[derive(Copy, Clone, Debug)]
+pub enum WindowCommand {
+    /// `s`: 上下分割当前窗口
+    SplitHorizontal,
+    /// `v`: 左右分割当前窗口
+    SplitVertical,
+    /// `h`/`j`/`k`/`l`: 焦点移动到相邻窗口
+    Focus(Direction),
+    /// `w`: 焦点移动到下一个窗口
+    FocusNext,
+    /// `+`: 增加窗口高度
+    Taller,
+    /// `-`: 减少窗口高度
+    Shorter,
+    /// `>`: 增加窗口宽度
+    Wider,
+    /// `<`: 减少窗口宽度
+    Narrower,
+    /// `=`: 平分所有窗口
+    Equalize,
+    /// `c`/`q`: 关闭当前窗口
+    Close,
+    /// `o`: 只保留当前窗口
+    Only,
+}
+
+/// 缓冲区选择器中的操作
+#[derive(Copy, Clone, Debug)]
+pub enum PickerCommand {
+    Up,
+    Down,
+    /// 切换到选中的缓冲区
+    Accept,
+    /// 关闭选中的缓冲区
+    Delete,
+    Cancel,
+}
+
+/// 文件查找器中的操作
+#[derive(Copy, Clone, Debug)]
+pub enum FinderCommand {
+    /// 在查询末尾输入字符
+    Insert(char),
+    /// 删除查询的最后一个字符
+    Backspace,
+    Up,
+    Down,
+    /// 在新缓冲区中打开选中的文件
+    Accept,
+    Cancel,
+}
+
+/// 日志查看器中的操作
+#[derive(Copy, Clone, Debug)]
+pub enum LogCommand {
+    Scroll(Direction),
+    /// 切换到下一个过滤标签
+    NextFilter,
+    /// 切换到上一个过滤标签
+    PreviousFilter,
+    Close,
+}
+
+/// 文件被外部修改时的处理方式
+#[derive(Copy, Clone, Debug)]
+pub enum DiskCommand {
+    /// 从磁盘重新加载
+    Reload,
+    /// 保留缓冲区的内容
+    Keep,
+    /// 查看缓冲区与磁盘文件的差异
+    Diff,
+}
+
+/// 发现旧交换文件时的处理方式
+#[derive(Copy, Clone, Debug)]
+pub enum SwapCommand {
+    /// 用交换文件的内容替换缓冲区
+    Restore,
+    /// 查看文件与交换文件的差异
+    Diff,
+    /// 删除交换文件
+    Delete,
+    /// 忽略交换文件继续编辑, 它会在缓冲区修改后被覆盖
+    Ignore,
+}
+
+/// 目录浏览缓冲区中的操作
+#[derive(Copy, Clone, Debug)]
+pub enum ExplorerCommand {
+    /// 在当前窗口打开光标处的文件或进入目录
+    Open,
+    /// 上下分割当前窗口后在新窗口中打开
+    OpenSplitHorizontal,
+    /// 左右分割当前窗口后在新窗口中打开
+    OpenSplitVertical,
+    /// 回到上级目录
+    Parent,
+    /// 新建文件或目录
+    Create,
+    /// 重命名光标处的条目
+    Rename,
+    /// 删除光标处的条目
+    Delete,
+    /// 重新读取目录
+    Refresh,
+}
+
+/// 状态栏输入提示中的按键
+#[derive(Copy, Clone, Debug)]
+pub enum InputCommand {
+    /// 输入字符
+    Insert(char),
+    /// 删除最后一个字符
+    Backspace,
+    Confirm,
+    Cancel,
 }
 
 #[derive(Debug)]
 pub enum EditorCommand {
     Move(Direction),
+    /// 滚动视图, 光标仅在离开可见区域时被带动
+    Scroll(Scroll),
+    /// 将光标行重新定位到视图顶部/中部/底部
+    Recenter(Recenter),
     Resize(Size),
+    /// 跳转到匹配的括号
+    MatchBracket,
+    /// 等待下一个按键的前缀命令
+    Prefix(Prefix),
+    SetMark(char),
+    JumpToMark(char),
+    /// 跳转列表后退
+    JumpBack,
+    /// 跳转列表前进
+    JumpForward,
+    /// 显示/隐藏信息面板
+    ToggleInfo,
+    /// 打开全屏日志查看器
+    ShowLog,
+    /// 在光标处插入粘贴的文本
+    Paste(String),
+    /// 撤销最近一次编辑
+    Undo,
+    /// 切换到下一个缓冲区
+    NextBuffer,
+    /// 切换到上一个缓冲区
+    PreviousBuffer,
+    /// 打开缓冲区选择器
+    ShowBuffers,
+    /// 关闭当前缓冲区
+    CloseBuffer,
+    /// 缓冲区选择器中的操作
+    Picker(PickerCommand),
+    /// 打开文件查找器
+    ShowFinder,
+    /// 文件查找器中的操作
+    Finder(FinderCommand),
+    /// 窗口操作
+    Window(WindowCommand),
+    /// 日志查看器中的操作
+    Log(LogCommand),
+    /// 开始或停止跟随文件增长
+    ToggleFollow,
+    /// 外部修改提示中的选择
+    Disk(DiskCommand),
+    /// 旧交换文件提示中的选择
+    Swap(SwapCommand),
+    /// 目录浏览缓冲区中的操作
+    Explorer(ExplorerCommand),
+    /// 目录操作提示中的输入
+    Input(InputCommand),
+    /// 终端获得焦点, 检查文件是否被外部修改
+    FocusGained,
+    /// 终端失去焦点
+    FocusLost,
     Help,
+    /// 挂起到后台(`Ctrl+z`)
+    Suspend,
     Quit,
-    OtherKeyCommand(String),
+    /// 按键释放, 仅在终端报告事件类型时出现
+    KeyRelease(KeyEvent),
     OtherEvent(String),
 }
 
+/// 统一未启用键盘增强协议时有歧义的按键
+///
+/// 传统终端中 `Backspace` 可能发送与 `Ctrl+h` 相同的字节(0x08), 此时无法区分两者,
+/// 按更常见的 `Backspace` 处理, 帮助改用 `F1`
+pub fn resolve_legacy_key(event: Event) -> Event {
+    match event {
+        Event::Key(
+            key_event @ KeyEvent {
+                code: KeyCode::Char('h'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            },
+        ) => Event::Key(KeyEvent {
+            code: KeyCode::Backspace,
+            modifiers: KeyModifiers::NONE,
+            ..key_event
+        }),
+        _ => event,
+    }
+}
+
 impl TryFrom<Event> for EditorCommand {
     type Error = String;
     fn try_from(event: Event) -> Result<Self, Self::Error> {
         match event {
-            // 处理KeyPress
+            // 处理KeyPress, 按住不放产生的KeyRepeat视为重复按下
             Event::Key(KeyEvent {
                 code,
                 modifiers,
-                kind: KeyEventKind::Press,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
             }) => match (code, modifiers) {
                 (KeyCode::Char('q'), KeyModifiers::CONTROL) => Ok(Self::Quit),
-                (KeyCode::Char('h'), KeyModifiers::CONTROL) => Ok(Self::Help),
+                (KeyCode::Char('z'), KeyModifiers::CONTROL) => Ok(Self::Suspend),
+                // 未启用键盘增强协议时, Ctrl+h 已由 `resolve_legacy_key` 转换为 Backspace
+                (KeyCode::F(1), KeyModifiers::NONE)
+                | (KeyCode::Char('h'), KeyModifiers::CONTROL) => Ok(Self::Help),
                 (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => {
                     Ok(Self::Move(Direction::Up))
                 }
@@ -49,6 +291,36 @@ impl TryFrom<Event> for EditorCommand {
                 (KeyCode::Right, _) | (KeyCode::Char('l'), KeyModifiers::NONE) => {
                     Ok(Self::Move(Direction::Right))
                 }
+                (KeyCode::Char('%'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                    Ok(Self::MatchBracket)
+                }
+                (KeyCode::Char('m'), KeyModifiers::NONE) => Ok(Self::Prefix(Prefix::SetMark)),
+                (KeyCode::Char('\''), KeyModifiers::NONE) => Ok(Self::Prefix(Prefix::JumpToMark)),
+                (KeyCode::Char('z'), KeyModifiers::NONE) => Ok(Self::Prefix(Prefix::View)),
+                (KeyCode::Char(']'), KeyModifiers::NONE) => Ok(Self::Prefix(Prefix::Next)),
+                (KeyCode::Char('['), KeyModifiers::NONE) => Ok(Self::Prefix(Prefix::Previous)),
+                (KeyCode::Char('b'), KeyModifiers::CONTROL) => Ok(Self::ShowBuffers),
+                (KeyCode::Char('p'), KeyModifiers::CONTROL) => Ok(Self::ShowFinder),
+                (KeyCode::Char('x'), KeyModifiers::CONTROL) => Ok(Self::CloseBuffer),
+                (KeyCode::Char('w'), KeyModifiers::CONTROL) => Ok(Self::Prefix(Prefix::Window)),
+                (KeyCode::Char('e'), KeyModifiers::CONTROL) => Ok(Self::Scroll(Scroll::LineDown)),
+                (KeyCode::Char('y'), KeyModifiers::CONTROL) => Ok(Self::Scroll(Scroll::LineUp)),
+                (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+                    Ok(Self::Scroll(Scroll::HalfPageDown))
+                }
+                (KeyCode::Char('u'), KeyModifiers::CONTROL) => Ok(Self::Scroll(Scroll::HalfPageUp)),
+                (KeyCode::Char('o'), KeyModifiers::CONTROL) => Ok(Self::JumpBack),
+                (KeyCode::Char('l'), KeyModifiers::CONTROL) => Ok(Self::ToggleInfo),
+                (KeyCode::Char('g'), KeyModifiers::CONTROL) => Ok(Self::ShowLog),
+                (KeyCode::Char('u'), KeyModifiers::NONE) => Ok(Self::Undo),
+                (KeyCode::Char('F'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                    Ok(Self::ToggleFollow)
+                }
+                // 终端中 Ctrl+i 与 Tab 发送相同的字节
+                (KeyCode::Char('i'), KeyModifiers::CONTROL)
+                | (KeyCode::Tab, KeyModifiers::NONE) => Ok(Self::JumpForward),
+                (KeyCode::Home, KeyModifiers::CONTROL) => Ok(Self::Move(Direction::FileStart)),
+                (KeyCode::End, KeyModifiers::CONTROL) => Ok(Self::Move(Direction::FileEnd)),
                 (KeyCode::PageUp, _) => Ok(Self::Move(Direction::PageUp)),
                 (KeyCode::PageDown, _) => Ok(Self::Move(Direction::PageDown)),
                 (KeyCode::Home, _) => Ok(Self::Move(Direction::Home)),
@@ -61,13 +333,8 @@ impl TryFrom<Event> for EditorCommand {
                     }
                 }
             },
-            // 处理其他的KeyEvent, 包括KeyRelease和KeyRepeat
-            Event::Key(key_event) if key_event.kind != KeyEventKind::Press => {
-                Ok(Self::OtherKeyCommand(format!(
-                    "KeyEvent: code={},modifiers={},kind={:?},state={:?}",
-                    key_event.code, key_event.modifiers, key_event.kind, key_event.state
-                )))
-            }
+            // 处理KeyRelease
+            Event::Key(key_event) => Ok(Self::KeyRelease(key_event)),
             // 处理ResizeEvent
             Event::Resize(width, height) =>
             {
@@ -77,7 +344,325 @@ impl TryFrom<Event> for EditorCommand {
                     width: width as usize,
                 }))
             }
-            _ => Ok(Self::OtherEvent(format!("{event:?}"))),
+            // 启用 bracketed paste 时, 粘贴的文本作为一个事件到达
+            Event::Paste(text) => Ok(Self::Paste(text)),
+            Event::FocusGained => Ok(Self::FocusGained),
+            Event::FocusLost => Ok(Self::FocusLost),
+            Event::Mouse(_) => Ok(Self::OtherEvent(format!("{event:?}"))),
         }
     }
 }
+
+impl EditorCommand {
+    /// 解析前缀命令之后的按键
+    ///
+    /// # 返回
+    /// - `None`: 不是按键按下事件, 前缀继续等待
+    /// - `Some(Err)`: 按键不能与前缀组合
+    pub fn try_from_prefixed(prefix: Prefix, event: &Event) -> Option<Result<Self, String>> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return None;
+        };
+        let command = match (prefix, code, *modifiers) {
+            (Prefix::SetMark, KeyCode::Char(name), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Ok(Self::SetMark(*name))
+            }
+            (Prefix::JumpToMark, KeyCode::Char(name), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Ok(Self::JumpToMark(*name))
+            }
+            (Prefix::View, KeyCode::Char('t'), KeyModifiers::NONE) => {
+                Ok(Self::Recenter(Recenter::Top))
+            }
+            (Prefix::View, KeyCode::Char('z'), KeyModifiers::NONE) => {
+                Ok(Self::Recenter(Recenter::Middle))
+            }
+            (Prefix::View, KeyCode::Char('b'), KeyModifiers::NONE) => {
+                Ok(Self::Recenter(Recenter::Bottom))
+            }
+            (Prefix::Next, KeyCode::Char('b'), KeyModifiers::NONE) => Ok(Self::NextBuffer),
+            (Prefix::Previous, KeyCode::Char('b'), KeyModifiers::NONE) => Ok(Self::PreviousBuffer),
+            (Prefix::Window, code, modifiers) => match Self::window_command(*code, modifiers) {
+                Some(command) => Ok(Self::Window(command)),
+                None => Err(format!("Press <{prefix:?} {code}>")),
+            },
+            _ => Err(format!("Press <{prefix:?} {code}>")),
+        };
+        Some(command)
+    }
+
+    /// 解析 `Ctrl+w` 之后的窗口操作按键
+    fn window_command(code: KeyCode, modifiers: KeyModifiers) -> Option<WindowCommand> {
+        let command = match (code, modifiers) {
+            (KeyCode::Char('s'), KeyModifiers::NONE) => WindowCommand::SplitHorizontal,
+            (KeyCode::Char('v'), KeyModifiers::NONE) => WindowCommand::SplitVertical,
+            (KeyCode::Left, _) | (KeyCode::Char('h'), KeyModifiers::NONE) => {
+                WindowCommand::Focus(Direction::Left)
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => {
+                WindowCommand::Focus(Direction::Down)
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => {
+                WindowCommand::Focus(Direction::Up)
+            }
+            (KeyCode::Right, _) | (KeyCode::Char('l'), KeyModifiers::NONE) => {
+                WindowCommand::Focus(Direction::Right)
+            }
+            // `Ctrl+w Ctrl+w` 与 `Ctrl+w w` 相同
+            (KeyCode::Char('w'), KeyModifiers::NONE | KeyModifiers::CONTROL) => {
+                WindowCommand::FocusNext
+            }
+            (KeyCode::Char('+'), KeyModifiers::NONE | KeyModifiers::SHIFT) => WindowCommand::Taller,
+            (KeyCode::Char('-'), KeyModifiers::NONE) => WindowCommand::Shorter,
+            (KeyCode::Char('>'), KeyModifiers::NONE | KeyModifiers::SHIFT) => WindowCommand::Wider,
+            (KeyCode::Char('<'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                WindowCommand::Narrower
+            }
+            (KeyCode::Char('='), KeyModifiers::NONE) => WindowCommand::Equalize,
+            (KeyCode::Char('c' | 'q'), KeyModifiers::NONE) => WindowCommand::Close,
+            (KeyCode::Char('o'), KeyModifiers::NONE) => WindowCommand::Only,
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    /// 解析分页模式下的事件
+    ///
+    /// 按 `less` 的习惯映射常用按键, 其他事件按普通方式处理
+    pub fn try_from_pager(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q' | 'Q'), KeyModifiers::NONE | KeyModifiers::SHIFT) => Self::Quit,
+            (KeyCode::Char(' ' | 'f'), KeyModifiers::NONE) => Self::Move(Direction::PageDown),
+            (KeyCode::Char('b'), KeyModifiers::NONE) => Self::Move(Direction::PageUp),
+            (KeyCode::Char('d'), KeyModifiers::NONE) => Self::Scroll(Scroll::HalfPageDown),
+            (KeyCode::Char('u'), KeyModifiers::NONE) => Self::Scroll(Scroll::HalfPageUp),
+            (KeyCode::Enter, KeyModifiers::NONE) => Self::Move(Direction::Down),
+            (KeyCode::Char('g' | '<'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Self::Move(Direction::FileStart)
+            }
+            (KeyCode::Char('G' | '>'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                Self::Move(Direction::FileEnd)
+            }
+            _ => return Self::try_from(event),
+        };
+        Ok(command)
+    }
+
+    /// 解析日志查看器打开时的事件
+    ///
+    /// 按键只用于滚动、过滤和关闭日志, `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器, 其他事件按普通方式处理
+    pub fn try_from_log_viewer(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Esc | KeyCode::Char('q'), KeyModifiers::NONE)
+            | (KeyCode::Char('g'), KeyModifiers::CONTROL) => LogCommand::Close,
+            (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => {
+                LogCommand::Scroll(Direction::Up)
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => {
+                LogCommand::Scroll(Direction::Down)
+            }
+            (KeyCode::PageUp, _) => LogCommand::Scroll(Direction::PageUp),
+            (KeyCode::PageDown, _) => LogCommand::Scroll(Direction::PageDown),
+            (KeyCode::Home, _) | (KeyCode::Char('g'), KeyModifiers::NONE) => {
+                LogCommand::Scroll(Direction::FileStart)
+            }
+            (KeyCode::End, _) | (KeyCode::Char('G'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                LogCommand::Scroll(Direction::FileEnd)
+            }
+            (KeyCode::Tab, KeyModifiers::NONE) => LogCommand::NextFilter,
+            (KeyCode::BackTab, _) => LogCommand::PreviousFilter,
+            _ => return Err(format!("Press <{code}> in log viewer")),
+        };
+        Ok(Self::Log(command))
+    }
+
+    /// 解析缓冲区选择器打开时的事件
+    ///
+    /// 按键只用于选择、关闭缓冲区和退出选择器, `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器, 其他事件按普通方式处理
+    pub fn try_from_picker(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Esc | KeyCode::Char('q'), KeyModifiers::NONE)
+            | (KeyCode::Char('b'), KeyModifiers::CONTROL) => PickerCommand::Cancel,
+            (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => PickerCommand::Up,
+            (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => PickerCommand::Down,
+            (KeyCode::Enter, _) => PickerCommand::Accept,
+            (KeyCode::Char('d'), KeyModifiers::NONE) => PickerCommand::Delete,
+            _ => return Err(format!("Press <{code}> in buffer list")),
+        };
+        Ok(Self::Picker(command))
+    }
+
+    /// 解析文件查找器打开时的事件
+    ///
+    /// 输入的字符追加到查询, `Up` / `Down` (或 `Ctrl+p` / `Ctrl+n`)选择, `Enter` 打开, `Esc` 返回,
+    /// `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器, 其他事件按普通方式处理
+    pub fn try_from_finder(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Esc, _) => FinderCommand::Cancel,
+            (KeyCode::Enter, _) => FinderCommand::Accept,
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => FinderCommand::Up,
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => FinderCommand::Down,
+            (KeyCode::Backspace, _) => FinderCommand::Backspace,
+            (KeyCode::Char(ch), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                FinderCommand::Insert(ch)
+            }
+            _ => return Err(format!("Press <{code}> in file finder")),
+        };
+        Ok(Self::Finder(command))
+    }
+
+    /// 解析外部修改提示打开时的事件
+    ///
+    /// `r` 重新加载, `k` / `Esc` 保留缓冲区内容, `d` 查看差异, `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器
+    pub fn try_from_disk_prompt(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Char('r'), KeyModifiers::NONE) => DiskCommand::Reload,
+            (KeyCode::Esc | KeyCode::Char('k'), KeyModifiers::NONE) => DiskCommand::Keep,
+            (KeyCode::Char('d'), KeyModifiers::NONE) => DiskCommand::Diff,
+            _ => return Err(format!("Press <{code}> in reload prompt")),
+        };
+        Ok(Self::Disk(command))
+    }
+
+    /// 解析旧交换文件提示打开时的事件
+    ///
+    /// `r` 恢复, `d` 查看差异, `x` 删除, `e` / `Esc` 忽略, `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器
+    pub fn try_from_swap_prompt(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Char('r'), KeyModifiers::NONE) => SwapCommand::Restore,
+            (KeyCode::Char('d'), KeyModifiers::NONE) => SwapCommand::Diff,
+            (KeyCode::Char('x'), KeyModifiers::NONE) => SwapCommand::Delete,
+            (KeyCode::Esc | KeyCode::Char('e'), KeyModifiers::NONE) => SwapCommand::Ignore,
+            _ => return Err(format!("Press <{code}> in swap file prompt")),
+        };
+        Ok(Self::Swap(command))
+    }
+
+    /// 解析当前缓冲区为目录浏览时的事件
+    ///
+    /// `Enter` 打开, `-` / `Backspace` 上级目录, `s` / `v` 在分割出的窗口中打开,
+    /// `n` 新建, `r` 重命名, `d` 删除, `R` 刷新, 其他事件按普通方式处理
+    pub fn try_from_explorer(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Enter, KeyModifiers::NONE) => ExplorerCommand::Open,
+            (KeyCode::Char('-') | KeyCode::Backspace, KeyModifiers::NONE) => {
+                ExplorerCommand::Parent
+            }
+            (KeyCode::Char('s'), KeyModifiers::NONE) => ExplorerCommand::OpenSplitHorizontal,
+            (KeyCode::Char('v'), KeyModifiers::NONE) => ExplorerCommand::OpenSplitVertical,
+            (KeyCode::Char('n'), KeyModifiers::NONE) => ExplorerCommand::Create,
+            (KeyCode::Char('r'), KeyModifiers::NONE) => ExplorerCommand::Rename,
+            (KeyCode::Char('d'), KeyModifiers::NONE) => ExplorerCommand::Delete,
+            (KeyCode::Char('R'), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                ExplorerCommand::Refresh
+            }
+            _ => return Self::try_from(event),
+        };
+        Ok(Self::Explorer(command))
+    }
+
+    /// 解析目录操作提示打开时的事件
+    ///
+    /// 输入的字符追加到名称, `Enter` 确认, `Esc` 取消, `Ctrl+q` / `Ctrl+z` 仍然退出/挂起编辑器
+    pub fn try_from_explorer_prompt(event: Event) -> Result<Self, String> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) = event
+        else {
+            return Self::try_from(event);
+        };
+        let command = match (code, modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Ok(Self::Quit),
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => return Ok(Self::Suspend),
+            (KeyCode::Char(ch), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                InputCommand::Insert(ch)
+            }
+            (KeyCode::Backspace, KeyModifiers::NONE) => InputCommand::Backspace,
+            (KeyCode::Enter, KeyModifiers::NONE) => InputCommand::Confirm,
+            (KeyCode::Esc, KeyModifiers::NONE) => InputCommand::Cancel,
+            _ => return Err(format!("Press <{code}> in prompt")),
+        };
+        Ok(Self::Input(command))
+    }
+}
@@ -0,0 +1,299 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 14:30:52
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 14:30:52
+ * @FilePath: \rim\src\editor\frame.rs
+ * @Description: 单元格帧缓冲, 支持差量刷新
+ */
+//! 帧缓冲模块
+//!
+//! 视图每次都把完整画面绘制到内存中的 [`Frame`] (后缓冲),
+//! 再与上一次刷新到终端的帧(前缓冲)逐单元格比较, 只输出发生变化的单元格
+//!
+//! 这样各个功能可以随时整帧重绘, 无需手动维护脏标记, 也避免了整行清屏重绘造成的闪烁
+//!
+//! 控制字符(如制表符)和零宽字符在终端上占用的列数与单元格不一致, 写入时替换为 `·`,
+//! 保证每个单元格恰好对应终端上的一列
+
+use super::style::Style;
+use super::terminal::{Position, Size, TerminalBackend};
+use std::io::Error;
+use unicode_width::UnicodeWidthChar;
+
+/// 控制字符和零宽字符的替换字符, 与 `Line` 对零宽字素的处理一致
+const REPLACEMENT: char = '·';
+
+/// 字符写入单元格时的符号和占用列数
+fn cell_symbol(ch: char) -> (char, usize) {
+    match ch.width() {
+        Some(width) if width > 0 && !ch.is_control() => (ch, width),
+        _ => (REPLACEMENT, 1),
+    }
+}
+
+/// 屏幕单元格
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// 单元格字符, 全角字符的第二个单元格为 `None`
+    symbol: Option<char>,
+    /// 单元格样式
+    style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: Some(' '),
+            style: Style::default(),
+        }
+    }
+}
+
+/// 一帧画面
+///
+/// 按行优先存储所有单元格
+pub struct Frame {
+    size: Size,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    /// 创建指定尺寸的空白帧
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells: vec![Cell::default(); size.width.saturating_mul(size.height)],
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.size.width && y < self.size.height).then(|| y * self.size.width + x)
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Option<Cell> {
+        self.index(x, y).map(|index| self.cells[index])
+    }
+
+    fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
+        if let Some(index) = self.index(x, y) {
+            self.cells[index] = cell;
+        }
+    }
+
+    /// 在指定位置写入文本, 超出帧宽度的部分被截断
+    ///
+    /// # 返回
+    /// 文本实际占用的列数
+    pub fn print(&mut self, position: Position, text: &str) -> usize {
+        self.print_styled(position, text, Style::default())
+    }
+
+    /// 以指定样式在指定位置写入文本
+    ///
+    /// # 返回
+    /// 文本实际占用的列数
+    pub fn print_styled(&mut self, position: Position, text: &str, style: Style) -> usize {
+        let Position { mut x, y } = position;
+        if y >= self.size.height {
+            return 0;
+        }
+        for ch in text.chars() {
+            let (ch, width) = cell_symbol(ch);
+            if x + width > self.size.width {
+                break;
+            }
+            self.break_wide_cell(x, y);
+            self.break_wide_cell(x + width - 1, y);
+            self.set_cell(
+                x,
+                y,
+                Cell {
+                    symbol: Some(ch),
+                    style,
+                },
+            );
+            for offset in 1..width {
+                self.set_cell(
+                    x + offset,
+                    y,
+                    Cell {
+                        symbol: None,
+                        style,
+                    },
+                );
+            }
+            x += width;
+        }
+        x - position.x
+    }
+
+    /// 在已有样式上叠加样式, 不改变单元格内容
+    ///
+    /// # 参数
+    /// - `position`: 起始位置
+    /// - `width`: 叠加的列数
+    /// - `style`: 叠加的样式, 见 [`Style::patch`]
+    pub fn patch_style(&mut self, position: Position, width: usize, style: Style) {
+        let Position { x, y } = position;
+        for x in x..x.saturating_add(width) {
+            if let Some(index) = self.index(x, y) {
+                self.cells[index].style = self.cells[index].style.patch(style);
+            }
+        }
+    }
+
+    /// 覆盖全角字符的一半时, 把另一半替换为空格, 保持帧的一致性
+    fn break_wide_cell(&mut self, x: usize, y: usize) {
+        let Some(cell) = self.cell(x, y) else {
+            return;
+        };
+        if cell.symbol.is_none() && x > 0 {
+            // 覆盖全角字符的后半
+            self.set_cell(x - 1, y, Cell::default());
+        } else if self
+            .cell(x + 1, y)
+            .is_some_and(|next| next.symbol.is_none())
+        {
+            // 覆盖全角字符的前半
+            self.set_cell(x + 1, y, Cell::default());
+        }
+    }
+
+    /// 将本帧刷新到终端
+    ///
+    /// # 参数
+    /// - `terminal`: 终端后端
+    /// - `previous`: 上一次刷新到终端的帧; 为 `None` 或尺寸不同时清屏后完整重绘
+    ///
+    /// 只输出与 `previous` 不同的单元格, 相邻的变化单元格合并为一次输出
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果底层终端操作失败
+    pub fn flush(
+        &self,
+        terminal: &mut dyn TerminalBackend,
+        previous: Option<&Self>,
+    ) -> Result<(), Error> {
+        let blank;
+        let previous = match previous {
+            Some(previous) if previous.size == self.size => previous,
+            _ => {
+                terminal.clear_screen()?;
+                blank = Self::new(self.size);
+                &blank
+            }
+        };
+        let Size { height, width } = self.size;
+        for y in 0..height {
+            let mut x = 0;
+            while x < width {
+                if self.cell(x, y) == previous.cell(x, y) {
+                    x += 1;
+                    continue;
+                }
+                // 变化区间从全角字符的后半开始时, 从前半开始输出
+                if x > 0 && self.cell(x, y).is_some_and(|cell| cell.symbol.is_none()) {
+                    x -= 1;
+                }
+                terminal.move_cursor_to(Position { x, y })?;
+                let mut run = String::new();
+                let mut style = Style::default();
+                let mut first = true;
+                while x < width && (first || self.cell(x, y) != previous.cell(x, y)) {
+                    first = false;
+                    let Some(cell) = self.cell(x, y) else {
+                        break;
+                    };
+                    if cell.style != style && !run.is_empty() {
+                        Self::print_run(terminal, &run, style)?;
+                        run.clear();
+                    }
+                    style = cell.style;
+                    if let Some(symbol) = cell.symbol {
+                        run.push(symbol);
+                    }
+                    x += 1;
+                }
+                // 全角字符的后半未变化时也已随前半输出
+                while x < width && self.cell(x, y).is_some_and(|cell| cell.symbol.is_none()) {
+                    x += 1;
+                }
+                Self::print_run(terminal, &run, style)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn print_run(terminal: &mut dyn TerminalBackend, run: &str, style: Style) -> Result<(), Error> {
+        if run.is_empty() {
+            Ok(())
+        } else if style == Style::default() {
+            terminal.print(run)
+        } else {
+            terminal.print_styled(run, style)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::VirtualTerminal;
+
+    const SIZE: Size = Size {
+        height: 1,
+        width: 10,
+    };
+
+    /// 先完整刷新 `first`, 再在其上写入 `second` 并差量刷新, 返回终端上的行
+    fn flush_twice(first: &str, second: (usize, &str)) -> String {
+        let mut terminal = VirtualTerminal::new(SIZE);
+        let mut previous = Frame::new(SIZE);
+        previous.print(Position::default(), first);
+        previous.flush(&mut terminal, None).expect("flush");
+        let mut frame = Frame::new(SIZE);
+        frame.print(Position::default(), first);
+        frame.print(Position { x: second.0, y: 0 }, second.1);
+        frame.flush(&mut terminal, Some(&previous)).expect("flush");
+        let full = {
+            let mut terminal = VirtualTerminal::new(SIZE);
+            frame.flush(&mut terminal, None).expect("flush");
+            terminal.row(0)
+        };
+        let row = terminal.row(0);
+        assert_eq!(row, full, "diff flush differs from a full redraw");
+        row
+    }
+
+    #[test]
+    fn control_characters_take_one_visible_cell() {
+        let mut frame = Frame::new(SIZE);
+        assert_eq!(frame.print(Position::default(), "a\tb\u{1b}c"), 5);
+        assert_eq!(flush_twice("a\tb\tc", (4, "d")), "a·b·d");
+    }
+
+    #[test]
+    fn zero_width_characters_take_one_visible_cell() {
+        let mut frame = Frame::new(SIZE);
+        assert_eq!(frame.print(Position::default(), "a\u{301}b\u{200b}"), 4);
+        assert_eq!(flush_twice("a\u{301}b", (2, "c")), "a·c");
+    }
+
+    #[test]
+    fn wide_characters_take_two_cells() {
+        let mut frame = Frame::new(SIZE);
+        assert_eq!(frame.print(Position::default(), "中文ab"), 6);
+        assert_eq!(flush_twice("中文ab", (4, "xy")), "中文xy");
+        // 覆盖全角字符的后半, 前半变为空格
+        assert_eq!(flush_twice("中文ab", (1, "x")), " x文ab");
+        // 覆盖全角字符的前半, 后半变为空格
+        assert_eq!(flush_twice("a中文", (3, "x")), "a中x");
+    }
+
+    #[test]
+    fn wide_character_is_truncated_at_right_edge() {
+        let mut frame = Frame::new(SIZE);
+        assert_eq!(frame.print(Position::default(), "123456789中"), 9);
+    }
+}
@@ -0,0 +1,90 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 21:40:52
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 21:40:52
+ * @FilePath: \rim\src\editor\stdinreader.rs
+ * @Description: 后台读取管道输入
+ */
+//! 标准输入读取模块
+//!
+//! 管道输入(如 `git log | rim-viewer -`)在后台线程中逐行读取, 通过通道交给主循环,
+//! 主循环在等待按键的间隙把已到达的行追加到缓冲区
+//!
+//! 标准输入被管道占用时, `crossterm` 从 `/dev/tty` 读取按键, 两者互不干扰
+
+use std::io::{self, BufRead, ErrorKind};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// 每次最多取出的行数, 避免大量输入时界面长时间不刷新
+const MAX_LINES_PER_READ: usize = 10_000;
+
+/// 读取状态
+pub enum StdinStatus {
+    /// 仍在读取
+    Open,
+    /// 已取满 [`MAX_LINES_PER_READ`] 行, 还有已到达的行没有取出
+    Pending,
+    /// 输入已结束
+    Closed,
+    /// 读取出错, 值为错误描述
+    Failed(String),
+}
+
+/// 在后台线程中读取标准输入
+pub struct StdinReader {
+    /// 每项为一行(不含 `\n`)或读取错误
+    receiver: Receiver<Result<String, String>>,
+}
+
+impl StdinReader {
+    /// 启动读取线程
+    ///
+    /// 非 UTF-8 字节按有损方式转换, 行尾的 `\r` 保留给缓冲区识别换行符
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut stdin = io::stdin().lock();
+            let mut bytes = Vec::new();
+            loop {
+                bytes.clear();
+                match stdin.read_until(b'\n', &mut bytes) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        if bytes.last() == Some(&b'\n') {
+                            bytes.pop();
+                        }
+                        let line = String::from_utf8_lossy(&bytes).into_owned();
+                        // 接收端已丢弃(缓冲区被关闭)时停止读取
+                        if sender.send(Ok(line)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                    Err(err) => {
+                        let _ = sender.send(Err(err.to_string()));
+                        break;
+                    }
+                }
+            }
+        });
+        Self { receiver }
+    }
+
+    /// 取出已到达的行, 不阻塞
+    ///
+    /// # 参数
+    /// - `lines`: 取出的行追加到这里
+    pub fn read_lines(&self, lines: &mut Vec<String>) -> StdinStatus {
+        while lines.len() < MAX_LINES_PER_READ {
+            match self.receiver.try_recv() {
+                Ok(Ok(line)) => lines.push(line),
+                Ok(Err(err)) => return StdinStatus::Failed(err),
+                Err(TryRecvError::Empty) => return StdinStatus::Open,
+                Err(TryRecvError::Disconnected) => return StdinStatus::Closed,
+            }
+        }
+        StdinStatus::Pending
+    }
+}
@@ -0,0 +1,484 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 15:10:31
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 15:10:31
+ * @FilePath: \rim\src\editor\style.rs
+ * @Description: 颜色与文本属性
+ */
+//! 样式模块
+//!
+//! 定义与终端库无关的颜色 [`Color`] 和样式 [`Style`],
+//! 由终端后端负责转换为具体的控制序列
+//!
+//! 终端的颜色能力由 [`ColorLevel`] 描述, 主题中的 24 位颜色会被量化为
+//! 终端支持的调色板中最接近的颜色
+
+use std::env;
+
+/// 颜色
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    /// 24 位真彩色
+    Rgb(u8, u8, u8),
+    /// 调色板颜色, 0-15 为 16 色 ANSI 颜色, 16-255 为 256 色扩展
+    Indexed(u8),
+}
+
+/// 终端颜色能力
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    /// 不使用颜色, 只保留文本属性
+    Monochrome,
+    /// 16 色 ANSI
+    Ansi16,
+    /// 256 色
+    Ansi256,
+    /// 24 位真彩色
+    TrueColor,
+}
+
+impl ColorLevel {
+    /// 根据环境变量检测终端颜色能力
+    ///
+    /// ## 检测顺序
+    /// 1. `NO_COLOR` 非空: 不使用颜色
+    /// 2. `COLORTERM` 为 `truecolor` / `24bit`: 真彩色
+    /// 3. `TERM`: `dumb` 不使用颜色, 含 `truecolor` / `direct` 为真彩色, 含 `256color` 为 256 色
+    /// 4. 未设置 `TERM` 时, Windows Terminal (`WT_SESSION`) 为真彩色
+    /// 5. 其他情况保守地使用 16 色
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::detect_with(|name| env::var(name).unwrap_or_default())
+    }
+
+    /// 按 [`ColorLevel::detect`] 的规则检测, 环境变量由 `var` 提供, 未设置时为空字符串
+    fn detect_with(var: impl Fn(&str) -> String) -> Self {
+        if !var("NO_COLOR").is_empty() {
+            return Self::Monochrome;
+        }
+        let colorterm = var("COLORTERM").to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::TrueColor;
+        }
+        let term = var("TERM").to_ascii_lowercase();
+        if term == "dumb" {
+            Self::Monochrome
+        } else if term.contains("truecolor") || term.contains("direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term.is_empty() && !var("WT_SESSION").is_empty() {
+            Self::TrueColor
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// 确定使用的颜色能力
+    ///
+    /// # 参数
+    /// - `option`: 命令行 `--colors` 指定的颜色能力, 优先使用
+    /// - `config`: 配置文件中的颜色能力, `None` 表示 `auto`
+    ///
+    /// 两者都未指定时自动检测
+    #[must_use]
+    pub fn resolve(option: Option<Self>, config: Option<Self>) -> Self {
+        option.or(config).unwrap_or_else(Self::detect)
+    }
+
+    /// 解析颜色能力设置
+    ///
+    /// # 返回
+    /// `auto` 返回 `Ok(None)`, 表示需要自动检测
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Ok(None),
+            "truecolor" | "24bit" => Ok(Some(Self::TrueColor)),
+            "256" => Ok(Some(Self::Ansi256)),
+            "16" => Ok(Some(Self::Ansi16)),
+            "none" | "mono" | "monochrome" => Ok(Some(Self::Monochrome)),
+            _ => Err(format!(
+                "invalid color level {value:?}, expected auto / truecolor / 256 / 16 / none"
+            )),
+        }
+    }
+}
+
+/// 16 色 ANSI 颜色名, 下标即调色板索引
+const ANSI_COLOR_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_cyan",
+    "bright_white",
+];
+
+impl Color {
+    /// 解析颜色
+    ///
+    /// 支持 `#rrggbb`、ANSI 颜色名(如 `red`、`bright_blue`)和调色板索引(`0`-`255`)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if let Some(hex) = value.strip_prefix('#') {
+            if hex.len() == 6 && hex.is_ascii() {
+                let channel = |range| u8::from_str_radix(&hex[range], 16);
+                if let (Ok(r), Ok(g), Ok(b)) = (channel(0..2), channel(2..4), channel(4..6)) {
+                    return Ok(Self::Rgb(r, g, b));
+                }
+            }
+            return Err(format!("invalid hex color {value:?}"));
+        }
+        if let Some(index) = ANSI_COLOR_NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+            return Ok(Self::Indexed(index as u8));
+        }
+        value
+            .parse()
+            .map(Self::Indexed)
+            .map_err(|_| format!("invalid color {value:?}"))
+    }
+
+    /// 转换为终端支持的最接近的颜色
+    ///
+    /// # 返回
+    /// 终端不支持颜色时返回 `None`
+    #[must_use]
+    pub fn quantize(self, level: ColorLevel) -> Option<Self> {
+        match (level, self) {
+            (ColorLevel::Monochrome, _) => None,
+            (ColorLevel::TrueColor, _)
+            | (ColorLevel::Ansi256, Self::Indexed(_))
+            | (ColorLevel::Ansi16, Self::Indexed(0..=15)) => Some(self),
+            (ColorLevel::Ansi256, Self::Rgb(r, g, b)) => Some(Self::Indexed(nearest_256(r, g, b))),
+            (ColorLevel::Ansi16, _) => {
+                let (r, g, b) = self.to_rgb();
+                Some(Self::Indexed(nearest_index(&ANSI_16_RGB, (r, g, b))))
+            }
+        }
+    }
+
+    /// 转换为 RGB 分量, 调色板颜色使用 xterm 默认值
+    #[allow(clippy::integer_division)]
+    fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Self::Rgb(r, g, b) => (r, g, b),
+            Self::Indexed(index @ 0..=15) => ANSI_16_RGB[usize::from(index)],
+            Self::Indexed(index @ 16..=231) => {
+                let index = index - 16;
+                (
+                    CUBE_LEVELS[usize::from(index / 36)],
+                    CUBE_LEVELS[usize::from(index / 6 % 6)],
+                    CUBE_LEVELS[usize::from(index % 6)],
+                )
+            }
+            Self::Indexed(index) => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+        }
+    }
+}
+
+/// xterm 默认的 16 色 RGB 值
+const ANSI_16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// 256 色中 6x6x6 色立方每个分量的取值
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// 两个颜色的距离平方
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let diff = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    diff(r1, r2) + diff(g1, g2) + diff(b1, b2)
+}
+
+/// 在候选颜色中查找最接近的颜色的下标
+fn nearest_index(candidates: &[(u8, u8, u8)], rgb: (u8, u8, u8)) -> u8 {
+    let index = candidates
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, candidate)| distance(**candidate, rgb))
+        .map_or(0, |(index, _)| index);
+    u8::try_from(index).unwrap_or(0)
+}
+
+/// 查找 256 色调色板(色立方和灰阶)中最接近的颜色
+#[allow(clippy::integer_division)]
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    // 色立方分量取值 0, 95, 135, ... 的区间划分
+    let cube = |value: u8| match value {
+        0..48 => 0,
+        48..115 => 1,
+        _ => (value - 35) / 40,
+    };
+    let (ri, gi, bi) = (cube(r), cube(g), cube(b));
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (
+        CUBE_LEVELS[usize::from(ri)],
+        CUBE_LEVELS[usize::from(gi)],
+        CUBE_LEVELS[usize::from(bi)],
+    );
+    // 灰阶 232-255: 8, 18, ..., 238
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = u8::try_from(average.saturating_sub(3) / 10)
+        .unwrap_or(23)
+        .min(23);
+    let gray = 8 + gray_step * 10;
+    if distance((gray, gray, gray), (r, g, b)) < distance(cube_rgb, (r, g, b)) {
+        232 + gray_step
+    } else {
+        cube_index
+    }
+}
+
+/// 文本样式
+///
+/// 颜色为 `None` 时使用终端默认颜色
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    /// 反色样式
+    #[must_use]
+    pub fn reversed() -> Self {
+        Self {
+            reverse: true,
+            ..Self::default()
+        }
+    }
+
+    /// 在当前样式上叠加另一个样式
+    ///
+    /// `other` 中设置了的颜色覆盖当前颜色, 文本属性取并集
+    #[must_use]
+    pub fn patch(self, other: Self) -> Self {
+        Self {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: self.bold || other.bold,
+            italic: self.italic || other.italic,
+            underline: self.underline || other.underline,
+            reverse: self.reverse || other.reverse,
+        }
+    }
+
+    /// 按终端颜色能力转换样式中的颜色
+    ///
+    /// 不支持颜色时, 原本带背景色的样式改用反色, 以保留高亮效果
+    #[must_use]
+    pub fn quantize(self, level: ColorLevel) -> Self {
+        Self {
+            fg: self.fg.and_then(|fg| fg.quantize(level)),
+            bg: self.bg.and_then(|bg| bg.quantize(level)),
+            reverse: self.reverse || (level == ColorLevel::Monochrome && self.bg.is_some()),
+            ..self
+        }
+    }
+
+    /// 解析样式描述
+    ///
+    /// 由空白分隔的若干项组成, 例如 `fg=#61afef bg=black bold underline`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut style = Self::default();
+        for item in value.split_whitespace() {
+            match item.split_once('=') {
+                Some(("fg", color)) => style.fg = Some(Color::parse(color)?),
+                Some(("bg", color)) => style.bg = Some(Color::parse(color)?),
+                None if item == "bold" => style.bold = true,
+                None if item == "italic" => style.italic = true,
+                None if item == "underline" => style.underline = true,
+                None if item == "reverse" => style.reverse = true,
+                _ => return Err(format!("unknown style item {item:?}")),
+            }
+        }
+        Ok(style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 只设置了 `vars` 中变量时检测的颜色能力
+    fn detect(vars: &[(&str, &str)]) -> ColorLevel {
+        ColorLevel::detect_with(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_string())
+                .unwrap_or_default()
+        })
+    }
+
+    #[test]
+    fn no_color_disables_colors() {
+        assert_eq!(detect(&[("NO_COLOR", "1")]), ColorLevel::Monochrome);
+        assert_eq!(
+            detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]),
+            ColorLevel::Monochrome
+        );
+        // 空的 NO_COLOR 视为未设置
+        assert_eq!(
+            detect(&[("NO_COLOR", ""), ("TERM", "xterm-256color")]),
+            ColorLevel::Ansi256
+        );
+    }
+
+    #[test]
+    fn colorterm_enables_true_color() {
+        assert_eq!(detect(&[("COLORTERM", "truecolor")]), ColorLevel::TrueColor);
+        assert_eq!(
+            detect(&[("COLORTERM", "24BIT"), ("TERM", "dumb")]),
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            detect(&[("COLORTERM", "yes"), ("TERM", "xterm")]),
+            ColorLevel::Ansi16
+        );
+    }
+
+    #[test]
+    fn term_decides_without_colorterm() {
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorLevel::Monochrome);
+        assert_eq!(detect(&[("TERM", "xterm-direct")]), ColorLevel::TrueColor);
+        assert_eq!(detect(&[("TERM", "foot-truecolor")]), ColorLevel::TrueColor);
+        assert_eq!(detect(&[("TERM", "screen-256color")]), ColorLevel::Ansi256);
+        assert_eq!(detect(&[("TERM", "xterm")]), ColorLevel::Ansi16);
+        assert_eq!(detect(&[]), ColorLevel::Ansi16);
+    }
+
+    #[test]
+    fn windows_terminal_without_term_is_true_color() {
+        assert_eq!(detect(&[("WT_SESSION", "1")]), ColorLevel::TrueColor);
+        assert_eq!(
+            detect(&[("WT_SESSION", "1"), ("TERM", "xterm")]),
+            ColorLevel::Ansi16
+        );
+    }
+
+    #[test]
+    fn colors_option_overrides_config() {
+        let config = Some(ColorLevel::TrueColor);
+        assert_eq!(
+            ColorLevel::resolve(Some(ColorLevel::Monochrome), config),
+            ColorLevel::Monochrome
+        );
+        assert_eq!(
+            ColorLevel::resolve(Some(ColorLevel::Ansi256), None),
+            ColorLevel::Ansi256
+        );
+        assert_eq!(ColorLevel::resolve(None, config), ColorLevel::TrueColor);
+    }
+
+    #[test]
+    fn parse_color_levels() {
+        assert_eq!(ColorLevel::parse("auto"), Ok(None));
+        assert_eq!(ColorLevel::parse("24bit"), Ok(Some(ColorLevel::TrueColor)));
+        assert_eq!(ColorLevel::parse("256"), Ok(Some(ColorLevel::Ansi256)));
+        assert_eq!(ColorLevel::parse("16"), Ok(Some(ColorLevel::Ansi16)));
+        assert_eq!(ColorLevel::parse("None"), Ok(Some(ColorLevel::Monochrome)));
+        assert!(ColorLevel::parse("8").is_err());
+    }
+
+    #[test]
+    fn true_color_keeps_every_color() {
+        let rgb = Color::Rgb(1, 2, 3);
+        assert_eq!(rgb.quantize(ColorLevel::TrueColor), Some(rgb));
+        let indexed = Color::Indexed(200);
+        assert_eq!(indexed.quantize(ColorLevel::TrueColor), Some(indexed));
+    }
+
+    #[test]
+    fn ansi_256_maps_rgb_to_cube_or_gray() {
+        let quantize = |r, g, b| Color::Rgb(r, g, b).quantize(ColorLevel::Ansi256);
+        assert_eq!(quantize(255, 0, 0), Some(Color::Indexed(196)));
+        assert_eq!(quantize(0, 0, 0), Some(Color::Indexed(16)));
+        assert_eq!(quantize(255, 255, 255), Some(Color::Indexed(231)));
+        assert_eq!(quantize(128, 128, 128), Some(Color::Indexed(244)));
+        // 色立方分量 0 与 95 的分界
+        assert_eq!(quantize(47, 0, 255), Some(Color::Indexed(21)));
+        assert_eq!(quantize(48, 0, 255), Some(Color::Indexed(57)));
+        // 95 与 135 的分界
+        assert_eq!(quantize(114, 0, 255), Some(Color::Indexed(57)));
+        assert_eq!(quantize(116, 0, 255), Some(Color::Indexed(93)));
+        let indexed = Color::Indexed(100);
+        assert_eq!(indexed.quantize(ColorLevel::Ansi256), Some(indexed));
+    }
+
+    #[test]
+    fn ansi_16_maps_to_nearest_ansi_color() {
+        let quantize = |color: Color| color.quantize(ColorLevel::Ansi16);
+        assert_eq!(quantize(Color::Rgb(205, 0, 0)), Some(Color::Indexed(1)));
+        assert_eq!(quantize(Color::Rgb(250, 10, 10)), Some(Color::Indexed(9)));
+        assert_eq!(quantize(Color::Rgb(10, 10, 10)), Some(Color::Indexed(0)));
+        assert_eq!(quantize(Color::Indexed(15)), Some(Color::Indexed(15)));
+        // 256 色先换算为 RGB
+        assert_eq!(quantize(Color::Indexed(196)), Some(Color::Indexed(9)));
+        assert_eq!(quantize(Color::Indexed(255)), Some(Color::Indexed(7)));
+    }
+
+    #[test]
+    fn monochrome_drops_colors_and_reverses_backgrounds() {
+        assert_eq!(Color::Rgb(1, 2, 3).quantize(ColorLevel::Monochrome), None);
+        assert_eq!(Color::Indexed(1).quantize(ColorLevel::Monochrome), None);
+        let highlight = Style {
+            fg: Some(Color::Indexed(0)),
+            bg: Some(Color::Rgb(229, 192, 123)),
+            bold: true,
+            ..Style::default()
+        };
+        assert_eq!(
+            highlight.quantize(ColorLevel::Monochrome),
+            Style {
+                bold: true,
+                reverse: true,
+                ..Style::default()
+            }
+        );
+        let foreground = Style {
+            fg: Some(Color::Indexed(1)),
+            ..Style::default()
+        };
+        assert_eq!(
+            foreground.quantize(ColorLevel::Monochrome),
+            Style::default()
+        );
+    }
+}
@@ -13,18 +13,36 @@
 //! - 光标控制
 //! - 屏幕清理
 //! - 尺寸获取
+//! - 输入事件读取
 //!
-//! 使用 `crossterm` 库实现跨平台支持
+//! [`TerminalBackend`] 定义终端后端接口, 有两种实现:
+//! - [`Terminal`]: 使用 `crossterm` 库实现跨平台支持, 直接写入 `stdout`
+//! - [`VirtualTerminal`]: 内存中的虚拟屏幕, 用于无终端环境下的快照测试
 
-use crossterm::cursor::{Hide, MoveTo, Show};
-use crossterm::style::Print;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType};
+mod virtualterminal;
+
+use super::style::{Color, Style};
+use crossterm::cursor::{Hide, MoveTo, SetCursorStyle, Show};
+use crossterm::event::{
+    poll, read, DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange,
+    Event, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::style::{
+    Attribute, Color as CrosstermColor, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, size, supports_keyboard_enhancement, Clear, ClearType,
+};
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{queue, Command};
-use std::io::{stdout, Error, Write};
+use std::io::{stdout, Error, ErrorKind, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+pub use virtualterminal::VirtualTerminal;
 
 /// 终端尺寸表示
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Size {
     /// 终端高度（行数）
     pub height: usize,
@@ -35,7 +53,7 @@ pub struct Size {
 /// 光标位置坐标
 ///
 /// Position = where we are on the screen
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct Position {
     /// 水平位置（列索引，0-based）
     pub x: usize,
@@ -43,174 +61,321 @@ pub struct Position {
     pub y: usize,
 }
 
-/// 终端操作抽象
+/// 光标形状
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CursorShape {
+    /// 方块, 普通(浏览)状态
+    Block,
+    /// 竖线, 插入文本时
+    Bar,
+    /// 下划线, 替换字符或等待输入(前缀命令、提示)时
+    Underline,
+}
+
+/// 终端后端接口
+///
+/// 编辑器和视图只通过该接口操作终端, 以便替换为 [`VirtualTerminal`] 进行测试
 ///
-/// 所有方法均为静态方法，提供全局终端状态管理
-pub struct Terminal {}
+/// 除 [`TerminalBackend::initialize`]、[`TerminalBackend::terminate`] 外,
+/// 输出类操作都只是排队, 需要调用 [`TerminalBackend::execute`] 才会生效
+///
+/// # 错误
+/// 所有方法在底层终端操作失败时返回 `std::io::Error`
+pub trait TerminalBackend {
+    /// 初始化终端
+    fn initialize(&mut self) -> Result<(), Error>;
 
-impl Terminal {
+    /// 恢复终端原始状态
+    fn terminate(&mut self) -> Result<(), Error>;
+
+    /// 获取当前终端尺寸
+    fn size(&self) -> Result<Size, Error>;
+
+    /// 阻塞读取下一个输入事件
+    ///
+    /// # 错误
+    /// 输入已耗尽时返回 `ErrorKind::UnexpectedEof`
+    fn read_event(&mut self) -> Result<Event, Error>;
+
+    /// 等待输入事件, 最多等待 `timeout`
+    ///
+    /// # 返回
+    /// 有事件可读时返回 `true`; 默认实现总是返回 `true`, 由 [`TerminalBackend::read_event`] 阻塞等待
+    fn poll_event(&mut self, _timeout: Duration) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    /// 在当前位置打印字符串
+    fn print(&mut self, str: &str) -> Result<(), Error>;
+
+    /// 以指定样式在当前位置打印字符串, 打印完成后恢复默认样式
+    fn print_styled(&mut self, str: &str, style: Style) -> Result<(), Error>;
+
+    /// 清空整个屏幕
+    fn clear_screen(&mut self) -> Result<(), Error>;
+
+    /// 清空当前行
+    fn clear_line(&mut self) -> Result<(), Error>;
+
+    /// 移动光标到指定位置
+    fn move_cursor_to(&mut self, position: Position) -> Result<(), Error>;
+
+    /// 移动光标到指定行
+    fn move_cursor_to_row(&mut self, row: usize) -> Result<(), Error> {
+        self.move_cursor_to(Position { x: 0, y: row })
+    }
+
+    /// 隐藏光标
+    fn hide_cursor(&mut self) -> Result<(), Error>;
+
+    /// 显示光标
+    fn show_cursor(&mut self) -> Result<(), Error>;
+
+    /// 刷新输出缓冲区
+    fn execute(&mut self) -> Result<(), Error>;
+
+    /// 设置光标形状, [`TerminalBackend::terminate`] 时恢复用户原本的光标
+    fn set_cursor_shape(&mut self, shape: CursorShape) -> Result<(), Error>;
+
+    /// 挂起进程, 把终端交还给 shell, 恢复后重新初始化终端
+    ///
+    /// # 错误
+    /// 平台不支持挂起时返回 `ErrorKind::Unsupported`, 此时终端状态不变
+    fn suspend(&mut self) -> Result<(), Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "suspend is not supported",
+        ))
+    }
+
+    /// 是否启用了键盘增强协议
+    ///
+    /// 启用时修饰键没有歧义(如 `Ctrl+h` 与 `Backspace`), 并且会报告按键的重复和释放
+    fn keyboard_enhancement(&self) -> bool {
+        false
+    }
+}
+
+/// 是否已向终端推入键盘增强标志
+///
+/// 终端是全局资源, panic hook 中的 [`Terminal`] 也需要知道是否要弹出标志
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// 基于 `crossterm` 的终端后端
+///
+/// 终端是全局资源, 该类型不持有状态, 所有操作直接作用于 `stdout`
+#[derive(Default)]
+pub struct Terminal;
+
+impl TerminalBackend for Terminal {
     /// 初始化终端
     ///
     /// 执行以下操作：
     /// 1. 启用raw mode
     /// 2. 进入备用Screen, 防止应用输出污染终端历史
-    /// 3. 清屏
-    /// 4. 移动光标到原点 (0, 0)
-    ///
-    /// # 错误
-    /// 返回 `std::io::Error` 如果底层终端操作失败
-    pub fn initialize() -> Result<(), Error> {
+    /// 3. 启用bracketed paste, 粘贴的文本作为一个 `Event::Paste` 到达
+    /// 4. 启用焦点事件, 终端切回前台时检查文件是否被外部修改
+    /// 5. 终端支持时启用键盘增强协议(kitty protocol)
+    /// 6. 清屏
+    /// 7. 移动光标到原点 (0, 0)
+    fn initialize(&mut self) -> Result<(), Error> {
         enable_raw_mode()?;
-        Self::enter_alternate_screen()?;
-        Self::clear_screen()?;
-        Self::move_cursor_to(Position { x: 0, y: 0 })?;
-        Self::execute()?;
+        Self::queue_command(EnterAlternateScreen)?;
+        Self::queue_command(EnableBracketedPaste)?;
+        Self::queue_command(EnableFocusChange)?;
+        // 查询失败时视为不支持
+        if supports_keyboard_enhancement().unwrap_or(false) {
+            Self::queue_command(PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+            ))?;
+            KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+        }
+        self.clear_screen()?;
+        self.move_cursor_to(Position { x: 0, y: 0 })?;
+        self.execute()?;
         Ok(())
     }
 
     /// 恢复终端原始状态
     ///
     /// 执行以下操作：
-    /// 1. 退出备用Screen, 回到主Screen
-    /// 2. 显示光标
-    /// 3. 刷新输出缓存
-    /// 4. 禁用原始模式
-    ///
-    /// # 错误
-    /// 返回 `std::io::Error` 如果底层终端操作失败
-    pub fn terminate() -> Result<(), Error> {
-        Self::leave_alternate_screen()?;
-        Self::show_cursor()?;
-        Self::execute()?;
+    /// 1. 恢复键盘增强标志, 禁用bracketed paste和焦点事件
+    /// 2. 退出备用Screen, 回到主Screen
+    /// 3. 恢复用户原本的光标形状并显示光标
+    /// 4. 刷新输出缓存
+    /// 5. 禁用原始模式
+    fn terminate(&mut self) -> Result<(), Error> {
+        if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+            Self::queue_command(PopKeyboardEnhancementFlags)?;
+        }
+        Self::queue_command(DisableBracketedPaste)?;
+        Self::queue_command(DisableFocusChange)?;
+        Self::queue_command(LeaveAlternateScreen)?;
+        Self::queue_command(SetCursorStyle::DefaultUserShape)?;
+        self.show_cursor()?;
+        self.execute()?;
         disable_raw_mode()?;
         Ok(())
     }
 
-    /// 进入备用屏幕
-    pub fn enter_alternate_screen() -> Result<(), Error> {
-        Self::queue_command(EnterAlternateScreen)?;
-        Ok(())
+    /// 获取当前终端尺寸
+    ///
+    /// # 返回
+    /// [`Size`] 结构体包含当前终端的行数和列数
+    fn size(&self) -> Result<Size, Error> {
+        let (width, height) = size()?;
+        #[allow(clippy::as_conversions)]
+        let width = width as usize;
+        #[allow(clippy::as_conversions)]
+        let height = height as usize;
+        Ok(Size { height, width })
     }
 
-    /// 退出备用屏幕
-    pub fn leave_alternate_screen() -> Result<(), Error> {
-        Self::queue_command(LeaveAlternateScreen)?;
-        Ok(())
+    fn read_event(&mut self) -> Result<Event, Error> {
+        read()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<bool, Error> {
+        poll(timeout)
     }
 
     /// 在当前位置打印字符串
     ///
-    /// # 参数
-    /// - `str`: 要打印的字符串内容
-    ///
-    /// # 注意
-    /// 需要配合 [`Terminal::execute`] 方法刷新输出
-    ///
     /// # 示例
     /// ```ignore
-    /// Terminal::print("Hello world!")?;
-    /// Terminal::execute()?;
+    /// terminal.print("Hello world!")?;
+    /// terminal.execute()?;
     /// ```
-    pub fn print(str: &str) -> Result<(), Error> {
+    fn print(&mut self, str: &str) -> Result<(), Error> {
         Self::queue_command(Print(str))?;
         Ok(())
     }
 
-    /// 清空整个屏幕
-    ///
-    /// # 注意
-    /// 需要配合 [`Terminal::execute`] 方法刷新输出
-    pub fn clear_screen() -> Result<(), Error> {
+    fn print_styled(&mut self, str: &str, style: Style) -> Result<(), Error> {
+        if let Some(fg) = style.fg {
+            Self::queue_command(SetForegroundColor(Self::convert_color(fg)))?;
+        }
+        if let Some(bg) = style.bg {
+            Self::queue_command(SetBackgroundColor(Self::convert_color(bg)))?;
+        }
+        let attributes = [
+            (style.bold, Attribute::Bold),
+            (style.italic, Attribute::Italic),
+            (style.underline, Attribute::Underlined),
+            (style.reverse, Attribute::Reverse),
+        ];
+        for (enabled, attribute) in attributes {
+            if enabled {
+                Self::queue_command(SetAttribute(attribute))?;
+            }
+        }
+        Self::queue_command(Print(str))?;
+        // Reset 同时恢复默认颜色和属性
+        Self::queue_command(SetAttribute(Attribute::Reset))?;
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> Result<(), Error> {
         Self::queue_command(Clear(ClearType::All))?;
         Ok(())
     }
 
-    /// 清空当前行
-    ///
-    /// # 注意
-    /// 需要配合 [`Terminal::execute`] 方法刷新输出
-    pub fn clear_line() -> Result<(), Error> {
+    fn clear_line(&mut self) -> Result<(), Error> {
         Self::queue_command(Clear(ClearType::CurrentLine))?;
         Ok(())
     }
 
     /// 移动光标到指定位置
     ///
-    /// # 参数
-    /// - `position`: 目标位置坐标
-    ///
     /// # 注意
     /// 坐标超出终端尺寸会导致未定义行为
-    ///
-    /// # 示例
-    /// ```ignore
-    /// Terminal::move_cursor_to(Position { x: 5, y: 10 })?;
-    /// ```
-    pub fn move_cursor_to(postion: Position) -> Result<(), Error> {
-        #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
-        Self::queue_command(MoveTo(postion.x as u16, postion.y as u16))?;
-        Ok(())
-    }
-
-    /// 移动光标到指定行
-    ///
-    /// # 参数
-    /// - `row`: 目标行
-    pub fn move_cursor_to_row(row: usize) -> Result<(), Error> {
+    fn move_cursor_to(&mut self, position: Position) -> Result<(), Error> {
         #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
-        Self::queue_command(MoveTo(0, row as u16))?;
+        Self::queue_command(MoveTo(position.x as u16, position.y as u16))?;
         Ok(())
     }
 
-    /// 隐藏光标
-    ///
     /// 常用于全屏应用避免光标闪烁干扰
-    pub fn hide_cursor() -> Result<(), Error> {
+    fn hide_cursor(&mut self) -> Result<(), Error> {
         Self::queue_command(Hide)?;
         Ok(())
     }
 
-    /// 显示光标
-    ///
-    /// 恢复光标显示状态
-    pub fn show_cursor() -> Result<(), Error> {
+    fn show_cursor(&mut self) -> Result<(), Error> {
         Self::queue_command(Show)?;
         Ok(())
     }
 
-    /// 获取当前终端尺寸
-    ///
-    /// # 返回
-    /// [`Size`] 结构体包含当前终端的行数和列数
-    ///
-    /// # 错误
-    /// 返回 `std::io::Error` 如果获取尺寸失败
-    ///
-    /// # 示例
-    /// ```ignore
-    /// let size = Terminal::size()?;
-    /// println!("终端尺寸: {}x{}", size.width, size.height);
-    /// ```
-    pub fn size() -> Result<Size, Error> {
-        let (width, height) = size()?;
-        #[allow(clippy::as_conversions)]
-        let width = width as usize;
-        #[allow(clippy::as_conversions)]
-        let height = height as usize;
-        Ok(Size { height, width })
+    /// 设置光标形状, 均使用不闪烁的样式
+    fn set_cursor_shape(&mut self, shape: CursorShape) -> Result<(), Error> {
+        Self::queue_command(match shape {
+            CursorShape::Block => SetCursorStyle::SteadyBlock,
+            CursorShape::Bar => SetCursorStyle::SteadyBar,
+            CursorShape::Underline => SetCursorStyle::SteadyUnderScore,
+        })
     }
 
-    /// 刷新输出缓冲区
-    ///
     /// 执行所有排队中的终端命令
-    ///
-    /// # 注意
-    /// 所有队列操作（如打印、移动光标等）都需要调用此方法才能生效
-    pub fn execute() -> Result<(), Error> {
+    fn execute(&mut self) -> Result<(), Error> {
         stdout().flush()?;
         Ok(())
     }
 
+    /// 挂起到后台
+    ///
+    /// 执行以下操作：
+    /// 1. 恢复终端原始状态
+    /// 2. 向自身发送 `SIGTSTP`, 进程停止直到 shell 发送 `SIGCONT`(如 `fg`)
+    /// 3. 恢复后重新进入raw mode和备用Screen
+    #[cfg(unix)]
+    fn suspend(&mut self) -> Result<(), Error> {
+        self.terminate()?;
+        // SAFETY: `raise` 只向当前进程发送信号, 不涉及任何内存访问
+        let result = unsafe { libc::raise(libc::SIGTSTP) };
+        let raised = if result == 0 {
+            Ok(())
+        } else {
+            Err(Error::last_os_error())
+        };
+        self.initialize()?;
+        raised
+    }
+
+    fn keyboard_enhancement(&self) -> bool {
+        KEYBOARD_ENHANCED.load(Ordering::Relaxed)
+    }
+}
+
+impl Terminal {
+    /// 内部：转换为 `crossterm` 颜色
+    ///
+    /// 0-15 使用 ANSI 颜色名, 以兼容只支持 16 色的终端
+    fn convert_color(color: Color) -> CrosstermColor {
+        match color {
+            Color::Rgb(r, g, b) => CrosstermColor::Rgb { r, g, b },
+            Color::Indexed(index) => match index {
+                0 => CrosstermColor::Black,
+                1 => CrosstermColor::DarkRed,
+                2 => CrosstermColor::DarkGreen,
+                3 => CrosstermColor::DarkYellow,
+                4 => CrosstermColor::DarkBlue,
+                5 => CrosstermColor::DarkMagenta,
+                6 => CrosstermColor::DarkCyan,
+                7 => CrosstermColor::Grey,
+                8 => CrosstermColor::DarkGrey,
+                9 => CrosstermColor::Red,
+                10 => CrosstermColor::Green,
+                11 => CrosstermColor::Yellow,
+                12 => CrosstermColor::Blue,
+                13 => CrosstermColor::Magenta,
+                14 => CrosstermColor::Cyan,
+                15 => CrosstermColor::White,
+                _ => CrosstermColor::AnsiValue(index),
+            },
+        }
+    }
+
     /// 内部：排队终端命令
     fn queue_command(command: impl Command) -> Result<(), Error> {
         queue!(stdout(), command)?;
@@ -0,0 +1,305 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 13:20:15
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 13:20:15
+ * @FilePath: \rim\src\editor\terminal\virtualterminal.rs
+ * @Description: 内存中的虚拟终端
+ */
+//! 虚拟终端模块
+//!
+//! 在内存中模拟终端屏幕, 记录每个单元格的内容和光标位置,
+//! 并从预先排队的事件中读取输入, 用于在没有 TTY 的环境下对渲染结果做快照测试
+//!
+//! ```ignore
+//! let terminal = VirtualTerminal::new(Size { height: 10, width: 40 });
+//! terminal.push_keys("jjl");
+//! let args = Args {
+//!     files: vec![FileArg { path: "test.txt".into(), ..FileArg::default() }],
+//!     ..Args::default()
+//! };
+//! let mut editor = Editor::with_backend(Box::new(terminal.clone()), &args)?;
+//! editor.run();
+//! assert_eq!(terminal.row(5), "...");
+//! ```
+//!
+//! 完整的例子见 `tests/snapshot.rs`
+
+use super::{CursorShape, Position, Size, Style, TerminalBackend};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+use std::rc::Rc;
+use unicode_width::UnicodeWidthChar;
+
+/// 制表位间隔
+const TAB_WIDTH: usize = 8;
+
+/// 屏幕单元格
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Cell {
+    /// 单元格字符, 全角字符的第二个单元格为 `None`
+    ch: Option<char>,
+    /// 单元格样式
+    style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: Some(' '),
+            style: Style::default(),
+        }
+    }
+}
+
+/// 虚拟终端内部状态
+#[derive(Default)]
+struct Screen {
+    size: Size,
+    cells: Vec<Vec<Cell>>,
+    cursor: Position,
+    cursor_visible: bool,
+    /// 光标形状, `None` 表示终端默认形状
+    cursor_shape: Option<CursorShape>,
+    keyboard_enhancement: bool,
+    events: VecDeque<Event>,
+}
+
+impl Screen {
+    fn clear_row(&mut self, row: usize) {
+        if let Some(cells) = self.cells.get_mut(row) {
+            cells.fill(Cell::default());
+        }
+    }
+
+    /// 与真实终端一样处理字符: 制表符跳到下一个制表位, 其它控制字符和零宽字符不占列
+    fn put(&mut self, ch: char, style: Style) {
+        match ch {
+            '\r' => self.cursor.x = 0,
+            '\n' => self.cursor.y += 1,
+            '\t' => self.cursor.x += TAB_WIDTH - self.cursor.x % TAB_WIDTH,
+            _ if ch.is_control() || ch.width().unwrap_or(0) == 0 => {}
+            _ => {
+                let width = ch.width().unwrap_or(1);
+                let Position { x, y } = self.cursor;
+                if let Some(cells) = self.cells.get_mut(y) {
+                    if let Some(cell) = cells.get_mut(x) {
+                        *cell = Cell {
+                            ch: Some(ch),
+                            style,
+                        };
+                    }
+                    for offset in 1..width {
+                        if let Some(cell) = cells.get_mut(x + offset) {
+                            *cell = Cell { ch: None, style };
+                        }
+                    }
+                }
+                self.cursor.x += width;
+            }
+        }
+    }
+}
+
+/// 内存中的虚拟终端后端
+///
+/// 克隆得到的句柄共享同一块屏幕, 交给编辑器后仍可以从测试中读取绘制结果
+#[derive(Clone, Default)]
+pub struct VirtualTerminal {
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl VirtualTerminal {
+    /// 创建指定尺寸的空白虚拟终端
+    #[must_use]
+    pub fn new(size: Size) -> Self {
+        let terminal = Self::default();
+        terminal.resize(size);
+        terminal
+    }
+
+    /// 调整屏幕尺寸并清空内容
+    ///
+    /// 只修改屏幕本身, 如需通知编辑器请再排队一个 `Event::Resize`
+    pub fn resize(&self, size: Size) {
+        let mut screen = self.screen.borrow_mut();
+        screen.size = size;
+        screen.cells = vec![vec![Cell::default(); size.width]; size.height];
+    }
+
+    /// 模拟终端是否支持键盘增强协议
+    pub fn set_keyboard_enhancement(&self, enabled: bool) {
+        self.screen.borrow_mut().keyboard_enhancement = enabled;
+    }
+
+    /// 排队一个输入事件
+    pub fn push_event(&self, event: Event) {
+        self.screen.borrow_mut().events.push_back(event);
+    }
+
+    /// 排队一个按键事件
+    pub fn push_key(&self, code: KeyCode, modifiers: KeyModifiers) {
+        self.push_event(Event::Key(KeyEvent::new(code, modifiers)));
+    }
+
+    /// 把字符串中的每个字符作为一次按键排队
+    pub fn push_keys(&self, keys: &str) {
+        for ch in keys.chars() {
+            self.push_key(KeyCode::Char(ch), KeyModifiers::NONE);
+        }
+    }
+
+    /// 排队一次粘贴, 等同于启用 bracketed paste 的终端收到的粘贴事件
+    pub fn push_paste(&self, text: &str) {
+        self.push_event(Event::Paste(text.to_string()));
+    }
+
+    /// 获取一行的文本内容(不含行尾空白)
+    #[must_use]
+    pub fn row(&self, row: usize) -> String {
+        self.screen
+            .borrow()
+            .cells
+            .get(row)
+            .map_or_else(String::new, |cells| {
+                cells
+                    .iter()
+                    .filter_map(|cell| cell.ch)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+    }
+
+    /// 获取整个屏幕的文本快照, 每行以 `\n` 结尾
+    #[must_use]
+    pub fn snapshot(&self) -> String {
+        let height = self.screen.borrow().size.height;
+        (0..height).fold(String::new(), |mut snapshot, row| {
+            snapshot.push_str(&self.row(row));
+            snapshot.push('\n');
+            snapshot
+        })
+    }
+
+    /// 指定单元格的样式, 超出屏幕时为默认样式
+    #[must_use]
+    pub fn style_at(&self, position: Position) -> Style {
+        self.screen
+            .borrow()
+            .cells
+            .get(position.y)
+            .and_then(|cells| cells.get(position.x))
+            .map_or_else(Style::default, |cell| cell.style)
+    }
+
+    /// 当前光标位置
+    #[must_use]
+    pub fn cursor_position(&self) -> Position {
+        self.screen.borrow().cursor
+    }
+
+    /// 当前光标形状, `None` 表示终端默认形状
+    #[must_use]
+    pub fn cursor_shape(&self) -> Option<CursorShape> {
+        self.screen.borrow().cursor_shape
+    }
+
+    /// 光标是否可见
+    #[must_use]
+    pub fn is_cursor_visible(&self) -> bool {
+        self.screen.borrow().cursor_visible
+    }
+}
+
+impl TerminalBackend for VirtualTerminal {
+    fn initialize(&mut self) -> Result<(), Error> {
+        self.clear_screen()?;
+        self.move_cursor_to(Position::default())?;
+        Ok(())
+    }
+
+    fn terminate(&mut self) -> Result<(), Error> {
+        self.screen.borrow_mut().cursor_shape = None;
+        self.show_cursor()
+    }
+
+    fn size(&self) -> Result<Size, Error> {
+        Ok(self.screen.borrow().size)
+    }
+
+    fn read_event(&mut self) -> Result<Event, Error> {
+        self.screen
+            .borrow_mut()
+            .events
+            .pop_front()
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "no more queued events"))
+    }
+
+    fn print(&mut self, str: &str) -> Result<(), Error> {
+        let mut screen = self.screen.borrow_mut();
+        for ch in str.chars() {
+            screen.put(ch, Style::default());
+        }
+        Ok(())
+    }
+
+    fn print_styled(&mut self, str: &str, style: Style) -> Result<(), Error> {
+        let mut screen = self.screen.borrow_mut();
+        for ch in str.chars() {
+            screen.put(ch, style);
+        }
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> Result<(), Error> {
+        let mut screen = self.screen.borrow_mut();
+        for row in 0..screen.size.height {
+            screen.clear_row(row);
+        }
+        Ok(())
+    }
+
+    fn clear_line(&mut self) -> Result<(), Error> {
+        let mut screen = self.screen.borrow_mut();
+        let row = screen.cursor.y;
+        screen.clear_row(row);
+        Ok(())
+    }
+
+    fn move_cursor_to(&mut self, position: Position) -> Result<(), Error> {
+        self.screen.borrow_mut().cursor = position;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), Error> {
+        self.screen.borrow_mut().cursor_visible = false;
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> Result<(), Error> {
+        self.screen.borrow_mut().cursor_visible = true;
+        Ok(())
+    }
+
+    fn execute(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_cursor_shape(&mut self, shape: CursorShape) -> Result<(), Error> {
+        self.screen.borrow_mut().cursor_shape = Some(shape);
+        Ok(())
+    }
+
+    /// 模拟挂起后立即恢复, 恢复时屏幕被清空
+    fn suspend(&mut self) -> Result<(), Error> {
+        self.terminate()?;
+        self.initialize()
+    }
+
+    fn keyboard_enhancement(&self) -> bool {
+        self.screen.borrow().keyboard_enhancement
+    }
+}
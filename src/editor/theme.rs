@@ -0,0 +1,146 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 15:32:18
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 15:32:18
+ * @FilePath: \rim\src\editor\theme.rs
+ * @Description: 主题, 语义名称到样式的映射
+ */
+//! 主题模块
+//!
+//! 主题把界面元素的语义名称映射到 [`Style`], 文件格式与配置文件相同:
+//!
+//! ```text
+//! # 注释
+//! info.tag = fg=#61afef bold
+//! syntax.comment = fg=#7f848e italic
+//! ```
+//!
+//! 内置 `dark` 和 `light` 两个主题, 其他名称从配置目录下的
+//! `themes/<name>.theme` 加载, 也可以直接给出主题文件路径
+
+use super::style::{ColorLevel, Style};
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// 主题支持的语义名称, 每个名称都对应界面中的一处绘制
+pub const THEME_KEYS: [&str; 14] = [
+    // 文件内容, 以及缓冲区列表和文件查找器中未选中的条目
+    "text",
+    // 信息面板和日志查看器中的标签与消息
+    "info.tag",
+    "info.text",
+    // 当前窗口的状态栏, 以及各个全屏列表的标题栏
+    "status_bar",
+    // 分屏时非当前窗口的状态栏
+    "status_bar.inactive",
+    // 缓冲区列表和文件查找器中选中的条目
+    "selection",
+    // 文件查找器中与查询匹配的字符
+    "search_match",
+    // 行号、分屏分隔线和查找器的分隔线
+    "gutter",
+    // 文件末尾之后的 `~` 行, 文件查找器预览中的提示
+    "empty_row",
+    "bracket_match",
+    "syntax.keyword",
+    "syntax.string",
+    "syntax.comment",
+    "syntax.number",
+];
+
+/// 内置主题
+const BUILTIN_THEMES: [(&str, &str); 2] = [
+    ("dark", include_str!("theme/dark.theme")),
+    ("light", include_str!("theme/light.theme")),
+];
+
+/// 默认主题名
+pub const DEFAULT_THEME: &str = "dark";
+
+/// 主题
+pub struct Theme {
+    styles: HashMap<&'static str, Style>,
+}
+
+impl Default for Theme {
+    /// 不含颜色的基础主题, 只用反色区分状态栏、选中的条目和匹配括号
+    fn default() -> Self {
+        let styles = HashMap::from([
+            ("status_bar", Style::reversed()),
+            ("status_bar.inactive", Style::reversed()),
+            ("selection", Style::reversed()),
+            ("bracket_match", Style::reversed()),
+        ]);
+        Self { styles }
+    }
+}
+
+impl Theme {
+    /// 按名称或路径加载主题
+    ///
+    /// # 参数
+    /// - `name`: 内置主题名、配置目录中 `themes/<name>.theme` 的名称或主题文件路径
+    /// - `config_dir`: 配置目录
+    ///
+    /// # 错误
+    /// 主题文件不存在或格式错误时返回错误描述
+    pub fn load(name: &str, config_dir: Option<&Path>) -> Result<Self, String> {
+        if let Some((_, contents)) = BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name) {
+            return Self::parse(name, contents);
+        }
+        let path = if name.contains(['/', '\\'])
+            || Path::new(name)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("theme"))
+        {
+            PathBuf::from(name)
+        } else {
+            config_dir
+                .ok_or_else(|| format!("theme {name:?} not found"))?
+                .join("themes")
+                .join(format!("{name}.theme"))
+        };
+        let contents = read_to_string(&path)
+            .map_err(|err| format!("cannot read theme {}: {err}", path.display()))?;
+        Self::parse(name, &contents)
+    }
+
+    /// 解析主题文件内容
+    ///
+    /// 未出现的语义名称沿用基础主题
+    fn parse(name: &str, contents: &str) -> Result<Self, String> {
+        let mut theme = Self::default();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |err: String| format!("theme {name:?} line {}: {err}", index + 1);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `key = style`, found {line:?}")))?;
+            let key = key.trim();
+            let key = THEME_KEYS
+                .iter()
+                .find(|known| **known == key)
+                .ok_or_else(|| error(format!("unknown theme key {key:?}")))?;
+            let style = Style::parse(value.trim()).map_err(error)?;
+            theme.styles.insert(key, style);
+        }
+        Ok(theme)
+    }
+
+    /// 把所有样式转换为终端支持的颜色
+    pub fn quantize(&mut self, level: ColorLevel) {
+        for style in self.styles.values_mut() {
+            *style = style.quantize(level);
+        }
+    }
+
+    /// 获取语义名称对应的样式, 未定义时为默认样式
+    pub fn style(&self, key: &str) -> Style {
+        self.styles.get(key).copied().unwrap_or_default()
+    }
+}
@@ -10,31 +10,76 @@
 //!
 //! 负责管理编辑器界面渲染，包括：
 //! - 信息区域（事件日志）
-//! - 文本缓冲区显示
+//! - 文本缓冲区显示, 可同时打开多个缓冲区并切换
+//! - 窗口分割, 多个窗口可以显示同一个或不同的缓冲区
+//! - 发现文件被外部修改时提示重新加载、保留或查看差异
+//! - 已修改的缓冲区定期写入交换文件, 再次打开时提示恢复
+//! - 跨会话记住每个文件的光标、滚动位置和标记
+//! - 目录浏览: 打开目录时列出其中的条目, 可以进入子目录、打开、新建、重命名和删除文件
+//! - 文件查找器: 在工作目录中模糊查找文件并预览
+//! - 底部状态栏
 //! - 尺寸适应
 //!
-//! 使用双缓冲区策略优化渲染性能
+//! 视图只负责把完整画面绘制到 [`Frame`], 由帧缓冲的差量刷新优化渲染性能
 
+mod bracket;
 mod buffer;
+mod bufferlist;
+mod diff;
+mod eventlog;
+mod explorer;
+mod filestate;
+mod finder;
+mod gitignore;
+mod help;
+mod infopanel;
+mod layout;
 mod line;
 mod location;
-use super::editorcommand::{Direction, EditorCommand};
-use buffer::Buffer;
+mod logviewer;
+mod marks;
+mod statusbar;
+mod swap;
+mod syntax;
+mod window;
+use super::editorcommand::{
+    Direction, DiskCommand, EditorCommand, ExplorerCommand, FinderCommand, InputCommand,
+    LogCommand, PickerCommand, Recenter, Scroll, SwapCommand, WindowCommand,
+};
+use buffer::{Buffer, FileGrowth, LineChange, StdinState};
+use bufferlist::{BufferEntry, BufferPicker, BufferState};
+use eventlog::EventLog;
+use explorer::{EntryKind, Explorer, ExplorerPrompt};
+use filestate::{FileState, FileStates};
+use finder::Finder;
+use infopanel::InfoPanel;
+pub use infopanel::{InfoPosition, DEFAULT_INFO_HEIGHT};
+use layout::{Layout, Pane, Rect};
 use line::Line;
 use location::Location;
-use std::collections::VecDeque;
+use logviewer::LogViewer;
+use marks::{JumpList, Marks};
+use swap::{SwapContents, SwapFile};
 
-use crate::editor::terminal::{Position, Size, Terminal};
-use std::cmp::min;
+use crate::editor::frame::Frame;
+use crate::editor::terminal::{Position, Size};
+use crate::editor::theme::Theme;
+use crossterm::event::KeyEvent;
+use std::env;
 use std::fmt;
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::iter;
+use std::mem;
+use std::path::{Path, PathBuf};
+use syntax::TokenKind;
+use unicode_width::UnicodeWidthStr;
+use window::{Axis, WindowState, WindowTree, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH};
 
 const NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// 信息区域高度（固定行数）
-pub const INFO_SECTION_SIZE: usize = 5;
-
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct GraphemeLocation {
     pub grapheme_index: usize,
     pub line_index: usize,
@@ -53,13 +98,44 @@ impl fmt::Display for GraphemeLocation {
 
 /// 编辑器视图管理器
 ///
-/// 包含两个主要区域：
-/// 1. 顶部信息区域（显示事件日志）
-/// 2. 主文本缓冲区显示区域
+/// 包含三个区域：
+/// 1. 信息区域（显示事件日志）, 可位于缓冲区上方或下方, 也可以隐藏
+/// 2. 窗口区, 可以上下/左右分割为多个窗口, 每个窗口显示一个缓冲区
+/// 3. 底部状态栏
+// 各个布尔字段是互相独立的开关, 不适合合并为状态枚举
+#[allow(clippy::struct_excessive_bools)]
 pub struct View {
-    /// 事件日志队列（FIFO）
-    key_events_info: VecDeque<String>,
-    /// 文本缓冲区实例
+    /// 事件日志历史
+    log: EventLog,
+    /// 信息面板（最近的事件）
+    info_panel: InfoPanel,
+    /// 全屏日志查看器, 打开时替代整个界面
+    log_viewer: Option<LogViewer>,
+    /// 缓冲区选择器, 打开时替代整个界面
+    buffer_picker: Option<BufferPicker>,
+    /// 文件查找器, 打开时替代整个界面
+    finder: Option<Finder>,
+    /// 所有打开的缓冲区, 当前缓冲区的状态保存在视图自身的字段中, 其位置为 `None`
+    buffers: Vec<Option<BufferState>>,
+    /// 当前缓冲区在 `buffers` 中的下标
+    active: usize,
+    /// 等待再次确认关闭的已修改缓冲区下标
+    confirm_close: Option<usize>,
+    /// 文件被外部修改、等待选择处理方式的缓冲区下标
+    disk_prompt: Option<usize>,
+    /// 等待输入或确认的目录操作
+    explorer_prompt: Option<ExplorerPrompt>,
+    /// 各文件上次的光标、滚动位置和标记
+    file_states: FileStates,
+    /// 指定的缓存目录(交换文件), `None` 使用默认位置
+    cache_dir: Option<PathBuf>,
+    /// 窗口布局树
+    window_tree: WindowTree,
+    /// 所有窗口, 焦点窗口的状态保存在视图自身的字段中, 其位置为 `None`
+    windows: Vec<Option<WindowState>>,
+    /// 焦点窗口在 `windows` 中的下标
+    focused: usize,
+    /// 当前缓冲区(焦点窗口显示的缓冲区)
     buffer: Buffer,
     /// 当前终端尺寸
     size: Size,
@@ -69,39 +145,975 @@ pub struct View {
     grapheme_location: GraphemeLocation,
     /// `screen`的`buffer`区左上角和原始数据左上角的偏移
     scroll_offset: Location,
-    /// 缓冲区重绘标志
-    needs_redraw_buffer: bool,
-    /// 是否记录`KeyRelease`和`KeyRepeat`
-    only_log_key_press: bool,
+    /// 光标处括号所匹配的括号位置
+    matched_bracket: Option<GraphemeLocation>,
+    /// 命名标记
+    marks: Marks,
+    /// 跳转列表
+    jump_list: JumpList,
+    /// 光标与可见区域上下边缘保持的最小行数
+    scroll_off: usize,
+    /// 是否显示行号栏
+    line_numbers: bool,
+    /// 是否跟随当前缓冲区的增长, 光标固定在最后一行; 当前缓冲区改变时停止
+    follow: bool,
+    /// 只读模式(`--readonly` / `--pager`): 之后打开的缓冲区都只读, 不能在目录浏览中修改文件
+    read_only: bool,
+    /// 是否在日志中记录按键松开事件(需要终端支持键盘增强协议)
+    log_key_release: bool,
 }
 
-impl Default for View {
-    fn default() -> Self {
+impl View {
+    /// 创建指定终端尺寸的视图
+    pub fn new(size: Size) -> Self {
         Self {
-            key_events_info: VecDeque::default(),
+            log: EventLog::default(),
+            info_panel: InfoPanel::default(),
+            log_viewer: None,
+            buffer_picker: None,
+            finder: None,
+            buffers: vec![None],
+            active: 0,
+            confirm_close: None,
+            disk_prompt: None,
+            explorer_prompt: None,
+            file_states: FileStates::default(),
+            cache_dir: None,
+            window_tree: WindowTree::default(),
+            windows: vec![None],
+            focused: 0,
             buffer: Buffer::default(),
-            size: Terminal::size().unwrap_or_default(),
+            size,
             location: Location::default(),
             grapheme_location: GraphemeLocation::default(),
             scroll_offset: Location::default(),
-            needs_redraw_buffer: true,
-            only_log_key_press: true,
+            matched_bracket: None,
+            marks: Marks::default(),
+            jump_list: JumpList::default(),
+            scroll_off: 0,
+            line_numbers: false,
+            follow: false,
+            read_only: false,
+            log_key_release: false,
         }
     }
-}
 
-impl View {
-    /// 加载文件到缓冲区
+    /// 在新缓冲区中打开文件并切换过去
+    ///
+    /// # 参数
+    /// - `filename`: 文件路径, 为目录时打开目录浏览
+    ///
+    /// 当前缓冲区是未使用的空缓冲区时直接替换它; 成功加载后会记录打开事件, 失败时记录错误
+    ///
+    /// # 返回
+    /// 是否成功打开
+    pub fn load_file(&mut self, filename: &str) -> bool {
+        if Path::new(filename).is_dir() {
+            return self.open_directory(filename);
+        }
+        let buffer = match Buffer::load_file(filename) {
+            Ok(buffer) => buffer,
+            Err(err) => {
+                self.log_event("INFO", &format!("Cannot open {filename:?}: {err}"));
+                return false;
+            }
+        };
+        self.add_buffer(buffer);
+        match self.buffer.syntax {
+            Some(syntax) => {
+                self.log_event("INFO", &format!("{filename:?} opened ({}).", syntax.name));
+            }
+            None => self.log_event("INFO", &format!("{filename:?} opened.")),
+        }
+        self.attach_swap(filename);
+        self.restore_file_state(filename);
+        true
+    }
+
+    /// 读取各文件上次的视图状态, 之后打开的文件恢复到上次的位置
+    ///
+    /// # 参数
+    /// - `dir`: 指定的状态目录, `None` 时使用默认位置
+    pub fn load_file_states(&mut self, dir: Option<&Path>) {
+        match FileStates::load(dir) {
+            Ok(file_states) => self.file_states = file_states,
+            Err(err) => self.log_event("INFO", &format!("Cannot read view state: {err}")),
+        }
+    }
+
+    /// 记录所有打开的文件的视图状态并写入状态文件, 正常退出时调用
+    pub fn save_file_states(&mut self) {
+        for index in 0..self.buffers.len() {
+            self.record_file_state(index);
+        }
+        if let Err(err) = self.file_states.save() {
+            self.log_event("INFO", &format!("Cannot save view state: {err}"));
+        }
+    }
+
+    /// 恢复当前缓冲区的文件上次的光标、滚动位置和标记
+    fn restore_file_state(&mut self, filename: &str) {
+        let Some(state) = self.file_states.get(filename).cloned() else {
+            return;
+        };
+        for (name, location) in state.marks {
+            self.marks.set(name, location);
+        }
+        self.scroll_offset = state.scroll_offset;
+        let target = self.clamp_grapheme_location(state.grapheme_location);
+        self.set_grapheme_location(target);
+    }
+
+    /// 记录指定下标的缓冲区的视图状态, 没有对应文件的缓冲区不记录
+    fn record_file_state(&mut self, index: usize) {
+        let (buffer, state) = if index == self.active {
+            let state = FileState {
+                grapheme_location: self.grapheme_location,
+                scroll_offset: self.scroll_offset,
+                marks: self.marks.sorted(),
+            };
+            (&self.buffer, state)
+        } else {
+            let Some(parked) = self.buffers.get(index).and_then(Option::as_ref) else {
+                return;
+            };
+            let state = FileState {
+                grapheme_location: parked.grapheme_location,
+                scroll_offset: parked.scroll_offset,
+                marks: parked.marks.sorted(),
+            };
+            (&parked.buffer, state)
+        };
+        if buffer.stdin.is_some() {
+            return;
+        }
+        if let Some(file_name) = &buffer.file_name {
+            self.file_states.record(file_name, state);
+        }
+    }
+
+    /// 为当前缓冲区准备交换文件, 发现旧的交换文件时等待选择如何恢复
+    ///
+    /// 文件正被其他进程(或本进程的另一个缓冲区)编辑时不写交换文件;
+    /// 旧交换文件的内容与文件相同时直接删除
+    fn attach_swap(&mut self, filename: &str) {
+        let Some(path) = swap::swap_path(filename, self.cache_dir.as_deref()) else {
+            return;
+        };
+        // 另一个缓冲区可能还没有写入交换文件, 读取时找不到
+        let attached = self
+            .buffers
+            .iter()
+            .flatten()
+            .filter_map(|state| state.buffer.swap.as_ref())
+            .any(|swap| swap.path == path);
+        if attached {
+            self.log_event(
+                "INFO",
+                &format!(
+                    "{filename:?} is already open in another buffer, no swap file will be written."
+                ),
+            );
+            return;
+        }
+        let contents = match SwapContents::read(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                if err.kind() != ErrorKind::NotFound {
+                    self.log_event(
+                        "INFO",
+                        &format!("Ignoring swap file {}: {err}", path.display()),
+                    );
+                }
+                self.buffer.swap = Some(SwapFile::new(path));
+                return;
+            }
+        };
+        if contents.in_use() {
+            self.log_event(
+                "INFO",
+                &format!(
+                    "{filename:?} is already open in process {}, no swap file will be written.",
+                    contents.pid
+                ),
+            );
+            return;
+        }
+        let mut swap = SwapFile::new(path);
+        let unchanged = contents.lines.iter().map(String::as_str).eq(self
+            .buffer
+            .lines
+            .iter()
+            .map(Line::as_str));
+        if unchanged {
+            let _ = fs::remove_file(&swap.path);
+            self.log_event(
+                "INFO",
+                &format!("Removed an outdated swap file of {filename:?}."),
+            );
+        } else {
+            swap.stale = true;
+            self.log_event(
+                "INFO",
+                &format!(
+                    "Found a swap file of {filename:?}, rim may have crashed while editing it."
+                ),
+            );
+        }
+        self.buffer.swap = Some(swap);
+    }
+
+    /// 把所有已修改缓冲区的新内容写入交换文件, 删除已恢复为未修改的缓冲区的交换文件
+    pub fn write_swap_files(&mut self) {
+        let mut errors = Vec::new();
+        let parked = self
+            .buffers
+            .iter_mut()
+            .flatten()
+            .map(|state| &mut state.buffer);
+        for buffer in iter::once(&mut self.buffer).chain(parked) {
+            let Some(swap) = buffer.swap.as_mut().filter(|swap| !swap.stale) else {
+                continue;
+            };
+            let Some(file_name) = &buffer.file_name else {
+                continue;
+            };
+            if !buffer.modified {
+                swap.remove();
+            } else if swap.written != Some(buffer.edits) {
+                let lines = buffer.lines.iter().map(Line::as_str);
+                if let Err(err) = swap.write(file_name, lines, buffer.edits) {
+                    errors.push(format!("Cannot write swap file of {file_name:?}: {err}"));
+                    // 不再重试, 避免每次都报告同样的错误
+                    buffer.swap = None;
+                }
+            }
+        }
+        for error in errors {
+            self.log_event("INFO", &error);
+        }
+    }
+
+    /// 删除本次写入的所有交换文件, 正常退出时调用
+    pub fn remove_swap_files(&mut self) {
+        let parked = self
+            .buffers
+            .iter_mut()
+            .flatten()
+            .map(|state| &mut state.buffer);
+        for buffer in iter::once(&mut self.buffer).chain(parked) {
+            if let Some(swap) = buffer.swap.as_mut() {
+                swap.remove();
+            }
+        }
+    }
+
+    /// 是否在等待选择如何处理当前缓冲区的旧交换文件
+    pub fn is_swap_prompt_open(&self) -> bool {
+        self.buffer.swap.as_ref().is_some_and(|swap| swap.stale)
+    }
+
+    /// 打开接收标准输入的缓冲区并设为当前缓冲区, 内容随后由 [`View::append_stdin`] 追加
+    pub fn open_stdin(&mut self) {
+        self.add_buffer(Buffer::from_stdin());
+        self.log_event("INFO", "Reading from stdin...");
+    }
+
+    /// 把标准输入新到达的行追加到它的缓冲区
+    ///
+    /// # 返回
+    /// 该缓冲区已被关闭时返回 `false`, 调用方应停止读取
+    pub fn append_stdin(&mut self, lines: Vec<String>) -> bool {
+        if self.buffer.is_streaming() {
+            self.buffer.append_lines(lines);
+            if self.follow {
+                self.pin_to_bottom();
+            } else {
+                self.scroll_location_into_view();
+            }
+            return true;
+        }
+        match self.stdin_buffer() {
+            Some(buffer) => {
+                buffer.append_lines(lines);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 标准输入读取结束
+    ///
+    /// # 参数
+    /// - `error`: 读取出错时的错误描述
+    pub fn finish_stdin(&mut self, error: Option<&str>) {
+        let Some(buffer) = self.stdin_buffer() else {
+            return;
+        };
+        buffer.stdin = Some(StdinState::Finished);
+        let lines = buffer.lines.len();
+        match error {
+            Some(err) => self.log_event("INFO", &format!("Error reading stdin: {err}")),
+            None => self.log_event("INFO", &format!("stdin: {lines} lines read.")),
+        }
+    }
+
+    /// 是否正在跟随当前缓冲区的增长
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    /// 读取当前文件新追加的内容, 光标保持在最后一行
+    ///
+    /// 文件被截断时重新加载; 缓冲区被修改或读取失败时停止跟随
+    pub fn follow_file(&mut self) {
+        if !self.follow || self.buffer.file_name.is_none() {
+            return;
+        }
+        if self.buffer.modified {
+            self.stop_follow();
+            return;
+        }
+        match self.buffer.read_appended() {
+            Ok(FileGrowth::Unchanged) => return,
+            Ok(FileGrowth::Appended) => {}
+            Ok(FileGrowth::Truncated) => {
+                if !self.reload_buffer() {
+                    self.stop_follow();
+                    return;
+                }
+                self.log_event(
+                    "INFO",
+                    &format!("{} truncated, reloaded.", self.buffer.name()),
+                );
+            }
+            Err(err) => {
+                self.log_event(
+                    "INFO",
+                    &format!("Cannot read {}: {err}", self.buffer.name()),
+                );
+                self.stop_follow();
+                return;
+            }
+        }
+        self.pin_to_bottom();
+    }
+
+    /// 开始或停止跟随当前缓冲区
+    fn toggle_follow(&mut self) {
+        if self.follow {
+            self.stop_follow();
+            return;
+        }
+        if self.buffer.file_name.is_none() && self.buffer.stdin.is_none() {
+            self.log_event("INFO", "No file to follow.");
+            return;
+        }
+        if self.buffer.modified {
+            self.log_event("INFO", "Cannot follow a modified buffer.");
+            return;
+        }
+        self.follow = true;
+        self.log_event(
+            "INFO",
+            &format!(
+                "Following {}, move or press <F> to stop.",
+                self.buffer.name()
+            ),
+        );
+        self.follow_file();
+        self.pin_to_bottom();
+    }
+
+    /// 停止跟随
+    ///
+    /// 跟随期间追加的内容已经读入缓冲区, 把磁盘上的当前状态记为已知, 不再提示外部修改
+    fn stop_follow(&mut self) {
+        if self.follow {
+            self.follow = false;
+            self.buffer.keep_disk_version();
+            self.log_event("INFO", "Stopped following.");
+        }
+    }
+
+    /// 光标移到最后一行, 让最后一行显示在视图底部
+    fn pin_to_bottom(&mut self) {
+        let last = self.buffer.lines.len().saturating_sub(1);
+        self.set_grapheme_location(GraphemeLocation {
+            grapheme_index: 0,
+            line_index: last,
+        });
+    }
+
+    /// 处理外部修改提示的选择
+    ///
+    /// 查看差异时在新的缓冲区中打开差异, 回到原缓冲区后会再次提示
+    fn handle_disk_command(&mut self, command: DiskCommand) {
+        let Some(index) = self.disk_prompt.take() else {
+            return;
+        };
+        if index != self.active {
+            return;
+        }
+        let name = self.buffer.name().to_string();
+        match command {
+            DiskCommand::Reload => {
+                if self.reload_buffer() {
+                    self.log_event("INFO", &format!("{name} reloaded."));
+                }
+            }
+            DiskCommand::Keep => {
+                self.buffer.keep_disk_version();
+                self.log_event("INFO", &format!("Keeping the buffer contents of {name}."));
+            }
+            DiskCommand::Diff => self.show_disk_diff(),
+        }
+    }
+
+    /// 处理旧交换文件提示的选择
+    ///
+    /// 查看差异时在新的缓冲区中打开差异, 回到原缓冲区后会再次提示
+    fn handle_swap_command(&mut self, command: SwapCommand) {
+        let Some(swap) = self.buffer.swap.as_mut().filter(|swap| swap.stale) else {
+            return;
+        };
+        let path = swap.path.clone();
+        if !matches!(command, SwapCommand::Diff) {
+            swap.stale = false;
+        }
+        let name = self.buffer.name().to_string();
+        match command {
+            SwapCommand::Restore => match SwapContents::read(&path) {
+                Ok(contents) => {
+                    self.buffer
+                        .replace_lines(&contents.lines, self.grapheme_location);
+                    let target = self.clamp_grapheme_location(self.grapheme_location);
+                    self.set_grapheme_location(target);
+                    self.log_event(
+                        "EDIT",
+                        &format!("Restored {name} from the swap file, press <u> to undo."),
+                    );
+                }
+                Err(err) => {
+                    self.log_event("INFO", &format!("Cannot read {}: {err}", path.display()));
+                }
+            },
+            SwapCommand::Diff => match SwapContents::read(&path) {
+                Ok(contents) => {
+                    let swap_lines: Vec<&str> = contents.lines.iter().map(String::as_str).collect();
+                    let file_lines: Vec<&str> =
+                        self.buffer.lines.iter().map(Line::as_str).collect();
+                    let lines = diff::unified(
+                        &file_lines,
+                        &swap_lines,
+                        &format!("{name} (file)"),
+                        &format!("{name} (swap)"),
+                    );
+                    self.add_buffer(Buffer::scratch(format!("[swap diff] {name}"), lines));
+                    self.log_event(
+                        "INFO",
+                        &format!("Showing the unsaved changes of {name}, switch back to decide."),
+                    );
+                }
+                Err(err) => {
+                    self.log_event("INFO", &format!("Cannot read {}: {err}", path.display()));
+                }
+            },
+            SwapCommand::Delete => {
+                if let Err(err) = fs::remove_file(&path) {
+                    self.log_event("INFO", &format!("Cannot delete {}: {err}", path.display()));
+                } else {
+                    self.log_event("INFO", &format!("Deleted the swap file of {name}."));
+                }
+            }
+            SwapCommand::Ignore => {
+                self.log_event(
+                    "INFO",
+                    &format!("Editing {name} anyway, the swap file is replaced once it changes."),
+                );
+            }
+        }
+    }
+
+    /// 在新的目录浏览缓冲区中打开目录并切换过去
+    fn open_directory(&mut self, dir: &str) -> bool {
+        match Explorer::read(Path::new(dir)) {
+            Ok(explorer) => {
+                self.add_buffer(Buffer::directory(explorer));
+                self.log_event("INFO", &format!("{dir:?} opened."));
+                true
+            }
+            Err(err) => {
+                self.log_event("INFO", &format!("Cannot open {dir:?}: {err}"));
+                false
+            }
+        }
+    }
+
+    /// 当前缓冲区是否为目录浏览
+    pub fn is_explorer_active(&self) -> bool {
+        self.buffer.explorer.is_some()
+    }
+
+    /// 是否在等待目录操作的输入或确认
+    pub fn is_explorer_prompt_open(&self) -> bool {
+        self.explorer_prompt.is_some()
+    }
+
+    /// 处理目录浏览缓冲区中的操作, 只读模式下拒绝新建、重命名和删除
+    fn handle_explorer_command(&mut self, command: ExplorerCommand) {
+        let Some(explorer) = &self.buffer.explorer else {
+            return;
+        };
+        if self.read_only
+            && matches!(
+                command,
+                ExplorerCommand::Create | ExplorerCommand::Rename | ExplorerCommand::Delete
+            )
+        {
+            self.log_event("INFO", "Read-only mode, files cannot be changed.");
+            return;
+        }
+        let selected = explorer
+            .entry(self.grapheme_location.line_index)
+            .map(|entry| (entry.name.clone(), entry.kind));
+        match (command, selected) {
+            (ExplorerCommand::Parent, _)
+            | (ExplorerCommand::Open, Some((_, EntryKind::Parent))) => self.show_parent_directory(),
+            (ExplorerCommand::Open, Some((name, _))) => self.open_entry(&name, None),
+            (ExplorerCommand::OpenSplitHorizontal, Some((name, _))) => {
+                self.open_entry(&name, Some(Axis::Horizontal));
+            }
+            (ExplorerCommand::OpenSplitVertical, Some((name, _))) => {
+                self.open_entry(&name, Some(Axis::Vertical));
+            }
+            (ExplorerCommand::Refresh, selected) => {
+                let dir = explorer.dir.clone();
+                self.show_directory(&dir, selected.map(|(name, _)| name).as_deref());
+            }
+            (ExplorerCommand::Create, _) => {
+                self.explorer_prompt = Some(ExplorerPrompt::Create(String::new()));
+            }
+            (ExplorerCommand::Rename | ExplorerCommand::Delete, Some((_, EntryKind::Parent)))
+            | (_, None) => self.log_event("INFO", "No entry under the cursor."),
+            (ExplorerCommand::Rename, Some((name, _))) => {
+                self.explorer_prompt = Some(ExplorerPrompt::Rename {
+                    input: name.clone(),
+                    from: name,
+                });
+            }
+            (ExplorerCommand::Delete, Some((name, _))) => {
+                self.explorer_prompt = Some(ExplorerPrompt::Delete(name));
+            }
+        }
+    }
+
+    /// 打开目录浏览缓冲区中的条目: 目录在当前缓冲区中进入, 文件在新缓冲区中打开
+    ///
+    /// # 参数
+    /// - `name`: 条目名称
+    /// - `split`: 先分割窗口, 在新窗口中打开; 此时目录也在新缓冲区中打开
+    fn open_entry(&mut self, name: &str, split: Option<Axis>) {
+        let Some(explorer) = &self.buffer.explorer else {
+            return;
+        };
+        let path = explorer.dir.join(name);
+        if let Some(axis) = split {
+            let windows = self.windows.len();
+            self.split_window(axis);
+            if self.windows.len() == windows {
+                return;
+            }
+        }
+        if !path.is_dir() {
+            self.load_file(&explorer::display_path(&path));
+        } else if split.is_some() {
+            self.open_directory(&explorer::display_path(&path));
+        } else {
+            self.show_directory(&path, None);
+        }
+    }
+
+    /// 目录浏览回到上级目录, 光标停在原来的目录上
+    fn show_parent_directory(&mut self) {
+        let Some(explorer) = &self.buffer.explorer else {
+            return;
+        };
+        let Some(parent) = explorer.dir.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let name = explorer
+            .dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        self.show_directory(&parent, name.as_deref());
+    }
+
+    /// 在当前的目录浏览缓冲区中显示目录
+    ///
+    /// # 参数
+    /// - `dir`: 目录
+    /// - `select`: 光标所在的条目; 没有指定或找不到时, 刷新同一目录保持原来的行, 进入其他目录回到第一行
+    fn show_directory(&mut self, dir: &Path, select: Option<&str>) {
+        let explorer = match Explorer::read(dir) {
+            Ok(explorer) => explorer,
+            Err(err) => {
+                self.log_event("INFO", &format!("Cannot open {}: {err}", dir.display()));
+                return;
+            }
+        };
+        let same_dir = self
+            .buffer
+            .explorer
+            .as_ref()
+            .is_some_and(|current| current.dir == explorer.dir);
+        let line_index = match select.and_then(|name| explorer.position(name)) {
+            Some(line_index) => line_index,
+            None if same_dir => self.grapheme_location.line_index,
+            None => 0,
+        };
+        if !same_dir {
+            self.scroll_offset = Location::default();
+        }
+        self.buffer = Buffer::directory(explorer);
+        let target = self.clamp_grapheme_location(GraphemeLocation {
+            grapheme_index: 0,
+            line_index,
+        });
+        self.set_grapheme_location(target);
+    }
+
+    /// 处理目录操作提示中的输入
+    fn handle_explorer_input(&mut self, input: InputCommand) {
+        let Some(mut prompt) = self.explorer_prompt.take() else {
+            return;
+        };
+        if let Some(text) = prompt.input_mut() {
+            match input {
+                InputCommand::Insert(ch) => text.push(ch),
+                InputCommand::Backspace => {
+                    text.pop();
+                }
+                InputCommand::Confirm => {
+                    self.apply_explorer_prompt(&prompt);
+                    return;
+                }
+                InputCommand::Cancel => {
+                    self.log_event("INFO", "Cancelled.");
+                    return;
+                }
+            }
+            self.explorer_prompt = Some(prompt);
+        } else if matches!(input, InputCommand::Insert('y' | 'Y')) {
+            self.apply_explorer_prompt(&prompt);
+        } else {
+            self.log_event("INFO", "Cancelled.");
+        }
+    }
+
+    /// 执行已确认的目录操作, 之后刷新目录
+    fn apply_explorer_prompt(&mut self, prompt: &ExplorerPrompt) {
+        let Some(dir) = self
+            .buffer
+            .explorer
+            .as_ref()
+            .map(|explorer| explorer.dir.clone())
+        else {
+            return;
+        };
+        let (result, message, select) = match prompt {
+            ExplorerPrompt::Create(name) => (
+                explorer::create(&dir, name),
+                format!("Created {name}"),
+                Some(name.trim_end_matches('/')),
+            ),
+            ExplorerPrompt::Rename { from, input } => (
+                explorer::rename(&dir, from, input),
+                format!("Renamed {from} to {input}"),
+                Some(input.as_str()),
+            ),
+            ExplorerPrompt::Delete(name) => (
+                explorer::delete(&dir, name),
+                format!("Deleted {name}"),
+                None,
+            ),
+        };
+        match result {
+            Ok(()) => {
+                self.log_event("EDIT", &format!("{message}."));
+                self.show_directory(&dir, select);
+            }
+            Err(err) => self.log_event("INFO", &format!("{message} failed: {err}")),
+        }
+    }
+
+    /// 打开文件查找器, 在当前工作目录中查找文件
+    fn show_finder(&mut self) {
+        match env::current_dir() {
+            Ok(root) => self.finder = Some(Finder::open(root)),
+            Err(err) => self.log_event("INFO", &format!("Cannot search files: {err}")),
+        }
+    }
+
+    /// 处理文件查找器中的操作
+    fn handle_finder_command(&mut self, command: FinderCommand) {
+        let Some(finder) = self.finder.as_mut() else {
+            return;
+        };
+        match command {
+            FinderCommand::Insert(ch) => finder.insert(ch),
+            FinderCommand::Backspace => finder.backspace(),
+            FinderCommand::Up => finder.move_selection(false),
+            FinderCommand::Down => finder.move_selection(true),
+            FinderCommand::Accept => {
+                if let Some(path) = finder.selected_path().map(str::to_string) {
+                    self.finder = None;
+                    self.load_file(&path);
+                }
+            }
+            FinderCommand::Cancel => self.finder = None,
+        }
+    }
+
+    /// 在新的只读缓冲区中显示当前缓冲区与磁盘文件的差异
+    fn show_disk_diff(&mut self) {
+        let Some(file_name) = self.buffer.file_name.clone() else {
+            return;
+        };
+        let contents = match fs::read(&file_name) {
+            Ok(contents) => contents,
+            Err(err) => {
+                self.log_event("INFO", &format!("Cannot read {file_name:?}: {err}"));
+                return;
+            }
+        };
+        let disk = String::from_utf8_lossy(&contents);
+        let disk_lines: Vec<&str> = disk.trim_start_matches('\u{feff}').lines().collect();
+        let buffer_lines: Vec<&str> = self.buffer.lines.iter().map(Line::as_str).collect();
+        let lines = diff::unified(
+            &buffer_lines,
+            &disk_lines,
+            &format!("{file_name} (buffer)"),
+            &format!("{file_name} (disk)"),
+        );
+        // 只有换行符或字节顺序标记不同时没有可显示的差异
+        if lines.len() <= 2 {
+            self.buffer.keep_disk_version();
+            self.log_event(
+                "INFO",
+                &format!("{file_name:?} has no line differences, keeping the buffer."),
+            );
+            return;
+        }
+        self.add_buffer(Buffer::scratch(format!("[diff] {file_name}"), lines));
+        self.log_event(
+            "INFO",
+            &format!("Showing the changes to {file_name:?}, switch back to reload or keep."),
+        );
+    }
+
+    /// 从磁盘重新加载当前缓冲区, 保留只读状态, 光标留在原来的行
+    ///
+    /// # 返回
+    /// 缓冲区没有对应的文件或读取失败时返回 `false`
+    fn reload_buffer(&mut self) -> bool {
+        let Some(file_name) = self.buffer.file_name.clone() else {
+            return false;
+        };
+        match Buffer::load_file(&file_name) {
+            Ok(mut buffer) => {
+                buffer.read_only |= self.buffer.read_only;
+                buffer.swap = self.buffer.swap.take();
+                self.buffer = buffer;
+                let target = self.clamp_grapheme_location(self.grapheme_location);
+                self.set_grapheme_location(target);
+                true
+            }
+            Err(err) => {
+                self.log_event("INFO", &format!("Cannot reload {file_name:?}: {err}"));
+                false
+            }
+        }
+    }
+
+    /// 仍在读取标准输入的缓冲区
+    fn stdin_buffer(&mut self) -> Option<&mut Buffer> {
+        if self.buffer.is_streaming() {
+            return Some(&mut self.buffer);
+        }
+        self.buffers
+            .iter_mut()
+            .flatten()
+            .map(|state| &mut state.buffer)
+            .find(|buffer| buffer.is_streaming())
+    }
+
+    /// 添加缓冲区并设为当前缓冲区
+    ///
+    /// 当前缓冲区是未使用的空缓冲区时直接替换它; 跟随只针对原来的缓冲区, 因此停止跟随;
+    /// 只读模式下新缓冲区只读
+    fn add_buffer(&mut self, mut buffer: Buffer) {
+        self.stop_follow();
+        buffer.read_only |= self.read_only;
+        let unused = self.buffer.file_name.is_none()
+            && self.buffer.stdin.is_none()
+            && !self.buffer.modified
+            && self.buffer.is_empty();
+        if !unused {
+            self.buffers[self.active] = Some(self.park_buffer());
+            self.buffers.push(None);
+            self.active = self.buffers.len() - 1;
+        }
+        self.unpark_buffer(BufferState {
+            buffer,
+            ..BufferState::default()
+        });
+    }
+
+    /// 设置只读模式, 在打开文件之前调用; 只读模式下打开的缓冲区都只读, 目录浏览不能新建、重命名或删除文件
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        self.buffer.read_only |= read_only;
+    }
+
+    /// 把光标移动到指定位置, 并让该行位于视图中部
+    ///
+    /// # 参数
+    /// - `line`: 行号(从 1 开始), 超出范围时移动到最后一行
+    /// - `column`: 列号(从 1 开始, 按字素计), 超出范围时移动到行尾
+    pub fn go_to(&mut self, line: usize, column: usize) {
+        let target = self.clamp_grapheme_location(GraphemeLocation {
+            grapheme_index: column.saturating_sub(1),
+            line_index: line.saturating_sub(1),
+        });
+        #[allow(clippy::integer_division)]
+        let top = target.line_index.saturating_sub(self.buffer_height() / 2);
+        self.scroll_offset.y = top;
+        self.set_grapheme_location(target);
+        self.log_event("MOVE", &format!("Go to {target}"));
+    }
+
+    /// 切换到指定下标的缓冲区, 恢复它的光标、滚动位置、标记和跳转列表
+    pub fn switch_buffer(&mut self, index: usize) {
+        if index == self.active || index >= self.buffers.len() {
+            return;
+        }
+        self.activate_buffer(index);
+        let info = format!(
+            "Buffer {}/{}: {}",
+            index + 1,
+            self.buffers.len(),
+            self.buffer.name()
+        );
+        self.log_event("INFO", &info);
+    }
+
+    /// 设置滚动边距
     ///
     /// # 参数
-    /// - `filename`: 文件路径
+    /// - `scroll_off`: 光标与可见区域上下边缘保持的最小行数
+    pub fn set_scroll_off(&mut self, scroll_off: usize) {
+        self.scroll_off = scroll_off;
+        self.scroll_location_into_view();
+    }
+
+    /// 设置是否显示行号栏
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+        self.scroll_location_into_view();
+    }
+
+    /// 设置是否在日志中记录按键松开事件
+    pub fn set_log_key_release(&mut self, log_key_release: bool) {
+        self.log_key_release = log_key_release;
+    }
+
+    /// 设置信息面板
+    ///
+    /// # 参数
+    /// - `height`: 面板行数
+    /// - `position`: 面板位置
+    /// - `muted_tags`: 信息面板中不显示的事件标签
+    pub fn set_info_panel(&mut self, height: usize, position: InfoPosition, muted_tags: &[String]) {
+        self.info_panel.configure(height, position, muted_tags);
+        self.scroll_location_into_view();
+    }
+
+    /// 设置存放交换文件的缓存目录, `None` 使用默认位置
+    pub fn set_cache_dir(&mut self, dir: Option<PathBuf>) {
+        self.cache_dir = dir;
+    }
+
+    /// 设置同步写入的日志文件
+    pub fn set_log_file(&mut self, file: File) {
+        self.log.set_file(file);
+    }
+
+    /// 日志查看器是否打开
+    pub fn is_log_viewer_open(&self) -> bool {
+        self.log_viewer.is_some()
+    }
+
+    /// 缓冲区选择器是否打开
+    pub fn is_buffer_picker_open(&self) -> bool {
+        self.buffer_picker.is_some()
+    }
+
+    /// 文件查找器是否打开
+    pub fn is_finder_open(&self) -> bool {
+        self.finder.is_some()
+    }
+
+    /// 文件查找器是否仍在遍历目录或等待读取预览
+    pub fn is_finder_loading(&self) -> bool {
+        self.finder
+            .as_ref()
+            .is_some_and(|finder| finder.is_loading() || finder.is_preview_pending())
+    }
+
+    /// 把文件查找器在后台找到的文件加入列表, 选中项停留足够久后读取预览
+    pub fn poll_finder(&mut self) {
+        if let Some(finder) = self.finder.as_mut() {
+            finder.receive();
+            finder.load_due_preview();
+        }
+    }
+
+    /// 是否在等待选择如何处理外部修改
+    pub fn is_disk_prompt_open(&self) -> bool {
+        self.disk_prompt.is_some()
+    }
+
+    /// 检查当前缓冲区的文件是否被外部修改
+    ///
+    /// 缓冲区未修改且启用了自动重新加载时直接重新加载, 否则提示选择处理方式;
+    /// 打开日志查看器、缓冲区选择器或正在跟随文件时不检查
     ///
-    /// 成功加载后会记录打开事件
-    pub fn load_file(&mut self, filename: &str) {
-        if let Ok(buffer) = Buffer::load_file(filename) {
-            self.buffer = buffer;
-            self.log_event("INFO", &format!("{filename:?} opened."));
+    /// # 参数
+    /// - `auto_reload`: 是否自动重新加载未修改的缓冲区
+    pub fn check_disk(&mut self, auto_reload: bool) {
+        if self.disk_prompt.is_some()
+            || self.is_swap_prompt_open()
+            || self.follow
+            || self.log_viewer.is_some()
+            || self.buffer_picker.is_some()
+            || self.finder.is_some()
+            || !self.buffer.changed_on_disk()
+        {
+            return;
+        }
+        let name = self.buffer.name().to_string();
+        if auto_reload && !self.buffer.modified {
+            if self.reload_buffer() {
+                self.log_event("INFO", &format!("{name} changed on disk, reloaded."));
+            }
+            return;
         }
+        self.disk_prompt = Some(self.active);
+        self.log_event(
+            "INFO",
+            &format!("{name} changed on disk: [r]eload, [k]eep ours, [d]iff"),
+        );
     }
 
     /// 处理事件命令
@@ -110,58 +1122,231 @@ impl View {
     /// - `command`: 时间命令
     ///
     /// 根据不同命令执行不同路径
+    #[allow(clippy::too_many_lines)]
     pub fn handle_command(&mut self, command: EditorCommand) {
+        // 关闭已修改的缓冲区需要紧接着再按一次, 按键释放等事件不打断确认
+        let confirm_close = match command {
+            EditorCommand::KeyRelease(_) | EditorCommand::OtherEvent(_) => self.confirm_close,
+            _ => self.confirm_close.take(),
+        };
+        if stops_follow(&command) {
+            self.stop_follow();
+        }
         match command {
             EditorCommand::Help => {
                 self.help();
             }
+            EditorCommand::ToggleFollow => {
+                self.toggle_follow();
+            }
             EditorCommand::Move(direction) => {
                 self.move_text_location(direction);
             }
+            EditorCommand::Scroll(scroll) => {
+                self.scroll_view(scroll);
+            }
+            EditorCommand::Recenter(recenter) => {
+                self.recenter(recenter);
+            }
             EditorCommand::Resize(size) => {
                 self.resize(size);
             }
-            EditorCommand::OtherKeyCommand(string) => {
-                if !self.only_log_key_press {
-                    self.handle_other_key_command(&string);
+            EditorCommand::MatchBracket => {
+                self.jump_to_matching_bracket();
+            }
+            EditorCommand::SetMark(name) => {
+                self.set_mark(name);
+            }
+            EditorCommand::JumpToMark(name) => {
+                self.jump_to_mark(name);
+            }
+            EditorCommand::JumpBack => {
+                self.jump_back();
+            }
+            EditorCommand::JumpForward => {
+                self.jump_forward();
+            }
+            EditorCommand::ToggleInfo => {
+                self.toggle_info_panel();
+            }
+            EditorCommand::ShowLog => {
+                self.log_viewer = Some(LogViewer::default());
+            }
+            EditorCommand::Log(command) => {
+                self.handle_log_command(command);
+            }
+            EditorCommand::Paste(text) => {
+                self.paste(&text);
+            }
+            EditorCommand::Undo => {
+                self.undo();
+            }
+            EditorCommand::NextBuffer => {
+                self.cycle_buffer(true);
+            }
+            EditorCommand::PreviousBuffer => {
+                self.cycle_buffer(false);
+            }
+            EditorCommand::ShowBuffers => {
+                self.buffer_picker = Some(BufferPicker::new(self.active));
+            }
+            EditorCommand::ShowFinder => {
+                self.show_finder();
+            }
+            EditorCommand::Finder(command) => {
+                self.handle_finder_command(command);
+            }
+            EditorCommand::CloseBuffer => {
+                self.close_buffer(self.active, confirm_close == Some(self.active));
+            }
+            EditorCommand::Picker(command) => {
+                self.handle_picker_command(command, confirm_close);
+            }
+            EditorCommand::Window(command) => {
+                self.handle_window_command(command);
+            }
+            EditorCommand::Disk(command) => {
+                self.handle_disk_command(command);
+            }
+            EditorCommand::Swap(command) => {
+                self.handle_swap_command(command);
+            }
+            EditorCommand::Explorer(command) => {
+                self.handle_explorer_command(command);
+            }
+            EditorCommand::Input(input) => {
+                self.handle_explorer_input(input);
+            }
+            EditorCommand::KeyRelease(key_event) => {
+                if self.log_key_release {
+                    self.handle_key_release(key_event);
                 }
             }
             EditorCommand::OtherEvent(string) => {
                 self.handle_other_event(&string);
             }
-            EditorCommand::Quit => {}
+            EditorCommand::Quit
+            | EditorCommand::Suspend
+            | EditorCommand::Prefix(_)
+            | EditorCommand::FocusGained
+            | EditorCommand::FocusLost => {}
         }
     }
 
     /// 主渲染入口
     ///
-    /// 根据终端尺寸决定渲染策略：
-    /// - 足够大：渲染信息区域+缓冲区
-    /// - 太小：显示警告信息
-    pub fn render(&mut self) {
-        let Size { height, width: _ } = self.size;
-        let _ = Terminal::move_cursor_to(Position { x: 0, y: 0 });
-
-        if height > INFO_SECTION_SIZE {
-            self.render_info();
-            if self.needs_redraw_buffer {
-                if self.buffer.is_empty() {
-                    self.render_welcome_buffer();
-                } else {
-                    self.render_buffer();
-                }
+    /// 每次都把完整画面绘制到帧中, 由帧的差量刷新负责减少终端输出
+    ///
+    /// 渲染策略：
+    /// - 日志查看器打开时：全屏显示日志或按键帮助
+    /// - 缓冲区选择器打开时：全屏显示缓冲区列表
+    /// - 文件查找器打开时：全屏显示查找结果和预览
+    /// - 其他情况：按 [`Layout`] 渲染信息区域、缓冲区和状态栏, 空间不足时省略可选区域
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        if let Some(log_viewer) = &self.log_viewer {
+            log_viewer.render(frame, self.size, &self.log, theme);
+            return;
+        }
+        if let Some(picker) = &self.buffer_picker {
+            picker.render(frame, self.size, &self.buffer_entries(), theme);
+            return;
+        }
+        if let Some(finder) = &self.finder {
+            finder.render(frame, self.size, theme);
+            if let (Some((at, size)), Some(preview)) =
+                (Finder::preview_area(self.size), finder.preview())
+            {
+                let rect = Rect {
+                    x: at.x,
+                    y: at.y,
+                    width: size.width,
+                    height: size.height,
+                };
+                let pane = Pane::compute(rect, 0, false);
+                Self::render_buffer(frame, &pane, preview, Location::default(), None, theme);
             }
-        } else {
-            self.draw_size_warning();
+            return;
+        }
+        let layout = self.layout();
+        self.render_info(frame, &layout, theme);
+        let (windows, separators) = self.window_tree.layout(layout.windows);
+        let split = windows.len() > 1;
+        for (id, rect) in windows {
+            self.render_window(frame, id, rect, split, theme);
+        }
+        for separator in separators {
+            for y in separator.y..separator.y + separator.height {
+                frame.print_styled(Position { x: separator.x, y }, "│", theme.style("gutter"));
+            }
+        }
+        if let Some(row) = layout.status_row {
+            statusbar::render(
+                frame,
+                Position { x: 0, y: row },
+                self.size.width,
+                &self.buffer,
+                self.grapheme_location,
+                theme.style("status_bar"),
+            );
+        }
+        if let Some((row, text)) = self.prompt() {
+            let at = Position { x: 0, y: row };
+            frame.print_styled(at, &" ".repeat(self.size.width), theme.style("status_bar"));
+            frame.print_styled(at, &text, theme.style("status_bar"));
         }
     }
 
+    /// 等待选择的提示: 所在的行(状态栏, 没有状态栏时为最后一行)和文本
+    fn prompt(&self) -> Option<(usize, String)> {
+        let name = self.buffer.name();
+        let text = if let Some(prompt) = &self.explorer_prompt {
+            prompt.text()
+        } else if self.is_swap_prompt_open() {
+            format!("Swap file found for {name}: [r]estore, [d]iff, [x] delete, [e]dit anyway ")
+        } else if self.disk_prompt.is_some() {
+            format!("{name} changed on disk: [r]eload, [k]eep ours, [d]iff ")
+        } else {
+            return None;
+        };
+        let row = self
+            .layout()
+            .status_row
+            .or_else(|| self.size.height.checked_sub(1))?;
+        Some((row, text))
+    }
+
+    /// 当前终端尺寸
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
     pub fn get_cursor_position(&self) -> Position {
+        if self.log_viewer.is_some() {
+            return Position::default();
+        }
+        if let Some(picker) = &self.buffer_picker {
+            return Position {
+                x: 0,
+                y: picker.cursor_row(self.size),
+            };
+        }
+        if let Some(finder) = &self.finder {
+            return finder.cursor_position(self.size);
+        }
+        if let Some((row, text)) = self.prompt() {
+            return Position {
+                x: text.width().min(self.size.width.saturating_sub(1)),
+                y: row,
+            };
+        }
         let Location { x, y } = self.location;
         let Location { x: x_off, y: y_off } = self.scroll_offset;
+        let pane = self.pane();
+        // 极小的终端中光标也不能超出屏幕
         Position {
-            x: x.saturating_sub(x_off),
-            y: y.saturating_sub(y_off) + INFO_SECTION_SIZE,
+            x: (x.saturating_sub(x_off) + pane.left + pane.gutter_width)
+                .min(self.size.width.saturating_sub(1)),
+            y: (y.saturating_sub(y_off) + pane.top).min(self.size.height.saturating_sub(1)),
         }
     }
 
@@ -194,148 +1379,578 @@ impl View {
 
     /// 渲染信息区域
     ///
-    /// 在终端顶部显示事件日志队列
-    fn render_info(&mut self) {
-        let Size { height: _, width } = self.size;
-        for row in 0..INFO_SECTION_SIZE {
-            let _ = Terminal::move_cursor_to_row(row);
-            let _ = Terminal::clear_line();
-            if let Some(info) = self.key_events_info.get(row) {
-                let display_info = if info.len() > width {
-                    info.to_string()
-                } else {
-                    info.clone()
-                };
-                let _ = Terminal::print(&display_info);
+    /// 按布局在缓冲区上方或下方显示事件日志队列
+    fn render_info(&self, frame: &mut Frame, layout: &Layout, theme: &Theme) {
+        if layout.info_rows > 0 {
+            self.info_panel
+                .render(frame, layout.info_top, layout.info_rows, &self.log, theme);
+        }
+    }
+
+    /// 渲染一个窗口
+    ///
+    /// 焦点窗口使用视图自身的状态, 其他窗口使用保存的状态; 有多个窗口时每个窗口底部显示自己的状态行
+    fn render_window(&self, frame: &mut Frame, id: usize, rect: Rect, split: bool, theme: &Theme) {
+        let (buffer, grapheme_location, scroll_offset, matched_bracket, status_style) =
+            if id == self.focused {
+                (
+                    &self.buffer,
+                    self.grapheme_location,
+                    self.scroll_offset,
+                    self.matched_bracket,
+                    "status_bar",
+                )
             } else {
-                let _ = Terminal::print("");
-            }
+                let Some(Some(state)) = self.windows.get(id) else {
+                    return;
+                };
+                let Some(buffer) = self.buffer_at(state.buffer) else {
+                    return;
+                };
+                (
+                    buffer,
+                    state.grapheme_location,
+                    state.scroll_offset,
+                    None,
+                    "status_bar.inactive",
+                )
+            };
+        let pane = Pane::compute(rect, self.gutter_width(buffer), split);
+        if buffer.is_empty() && buffer.stdin.is_none() {
+            Self::render_welcome_buffer(frame, &pane, theme);
+        } else {
+            Self::render_buffer(frame, &pane, buffer, scroll_offset, matched_bracket, theme);
+        }
+        if let Some(row) = pane.status_row {
+            statusbar::render(
+                frame,
+                Position {
+                    x: pane.left,
+                    y: row,
+                },
+                pane.width,
+                buffer,
+                grapheme_location,
+                theme.style(status_style),
+            );
         }
     }
 
     /// 渲染文本缓冲区
     ///
-    /// 在信息区域下方显示文件内容
-    fn render_buffer(&mut self) {
-        let Size { height, width } = self.size;
-        let top_row = self.scroll_offset.y;
+    /// 在窗口中显示行号和文件内容, 并叠加语法高亮和括号匹配样式
+    fn render_buffer(
+        frame: &mut Frame,
+        pane: &Pane,
+        buffer: &Buffer,
+        scroll_offset: Location,
+        matched_bracket: Option<GraphemeLocation>,
+        theme: &Theme,
+    ) {
+        let Pane {
+            left,
+            top,
+            height,
+            gutter_width,
+            text_width: width,
+            ..
+        } = *pane;
+        let top_row = scroll_offset.y;
 
-        // let mut log_entries = Vec::new();
-
-        for row in INFO_SECTION_SIZE..height {
-            let _ = Terminal::move_cursor_to_row(row);
-            let _ = Terminal::clear_line();
-            let buffer_index = row - INFO_SECTION_SIZE;
-            if let Some(line) = self.buffer.lines.get(buffer_index.saturating_add(top_row)) {
-                let start = self.scroll_offset.x;
-                let end = self.scroll_offset.x.saturating_add(width);
-                let info = &line.get_display_string(start..end);
-                // log_entries.push((start, end, info.clone()));
-                let _ = Terminal::print(info);
-            } else {
-                Self::draw_empty_row();
+        for row in top..top + height {
+            let buffer_index = row - top;
+            let line_index = buffer_index.saturating_add(top_row);
+            let Some(line) = buffer.lines.get(line_index) else {
+                Self::draw_empty_row(frame, theme, Position { x: left, y: row });
+                continue;
+            };
+            if gutter_width > 0 {
+                let number = format!("{:>1$} ", line_index + 1, gutter_width - 1);
+                frame.print_styled(Position { x: left, y: row }, &number, theme.style("gutter"));
+            }
+            let text_left = left + gutter_width;
+            let start = scroll_offset.x;
+            let end = scroll_offset.x.saturating_add(width);
+            frame.print(
+                Position {
+                    x: text_left,
+                    y: row,
+                },
+                &line.get_display_string(start..end),
+            );
+            frame.patch_style(
+                Position {
+                    x: text_left,
+                    y: row,
+                },
+                width,
+                theme.style("text"),
+            );
+            // 语法高亮
+            for (grapheme_index, (col, grapheme_width)) in line.column_spans().enumerate() {
+                if col + grapheme_width <= start {
+                    continue;
+                }
+                if col >= end {
+                    break;
+                }
+                let key = match buffer.token_kind(line_index, grapheme_index) {
+                    TokenKind::Code => continue,
+                    TokenKind::Keyword => "syntax.keyword",
+                    TokenKind::Number => "syntax.number",
+                    TokenKind::String => "syntax.string",
+                    TokenKind::Comment => "syntax.comment",
+                };
+                let x = col.saturating_sub(start) + text_left;
+                frame.patch_style(Position { x, y: row }, grapheme_width, theme.style(key));
+            }
+            // 匹配括号高亮
+            let highlight = matched_bracket
+                .filter(|matched| matched.line_index == line_index)
+                .map(|matched| line.get_byte_offset(matched.grapheme_index))
+                .filter(|col| (start..end).contains(col));
+            if let Some(col) = highlight {
+                frame.patch_style(
+                    Position {
+                        x: col - start + text_left,
+                        y: row,
+                    },
+                    1,
+                    theme.style("bracket_match"),
+                );
             }
         }
-        // for (start, end, info) in log_entries {
-        //     self.log_event("TEST", &format!("{start} - {end}"));
-        //     self.log_event("TEST", &info);
-        // }
-        self.needs_redraw_buffer = false;
     }
 
-    fn render_welcome_buffer(&mut self) {
-        let Size { height, width: _ } = self.size;
-        for row in INFO_SECTION_SIZE..height {
-            let _ = Terminal::move_cursor_to_row(row);
-            let _ = Terminal::clear_line();
-            let buffer_index = row - INFO_SECTION_SIZE;
+    fn render_welcome_buffer(frame: &mut Frame, pane: &Pane, theme: &Theme) {
+        let Pane {
+            left, top, height, ..
+        } = *pane;
+        for row in top..top + height {
+            let buffer_index = row - top;
             #[allow(clippy::integer_division)]
-            let start_index = (height - INFO_SECTION_SIZE) / 3;
+            let start_index = height / 3;
+            let position = Position { x: left, y: row };
             if buffer_index == start_index {
-                self.draw_welcome_msg();
+                let welcome_msg = format!("{NAME} editor -- version {VERSION}");
+                Self::draw_centered_msg(frame, position, pane.width, &welcome_msg);
             } else if buffer_index == start_index + 2 {
-                self.draw_help_msg();
+                let help_msg = "Press <F1> for help; Press <Ctrl+q> to exit";
+                Self::draw_centered_msg(frame, position, pane.width, help_msg);
             } else {
-                Self::draw_empty_row();
+                Self::draw_empty_row(frame, theme, position);
             }
         }
-        self.needs_redraw_buffer = false;
     }
 
     /// 绘制空行指示符
     ///
     /// 在缓冲区末尾显示 `~` 符号表示空行
-    fn draw_empty_row() {
-        let _ = Terminal::print("~");
+    fn draw_empty_row(frame: &mut Frame, theme: &Theme, position: Position) {
+        frame.print_styled(position, "~", theme.style("empty_row"));
     }
 
-    /// 绘制欢迎指示符
+    /// 绘制居中的欢迎/帮助指示符
     ///
-    fn draw_welcome_msg(&self) {
-        let mut welcome_msg = format!("{NAME} editor -- version {VERSION}");
-        let width = self.size.width;
-        let len = welcome_msg.len();
+    /// 行首仍显示 `~`, 窗口过窄时截断
+    fn draw_centered_msg(frame: &mut Frame, position: Position, width: usize, msg: &str) {
+        let len = msg.len();
         #[allow(clippy::integer_division)]
         let padding = (width.saturating_sub(len)) / 2;
         let spaces = " ".repeat(padding.saturating_sub(1));
-        welcome_msg = format!("~{spaces}{welcome_msg}");
-        welcome_msg.truncate(width);
-        let _ = Terminal::print(&welcome_msg);
+        let line: String = format!("~{spaces}{msg}").chars().take(width).collect();
+        frame.print(position, &line);
     }
 
-    /// 绘制帮助指示符
+    /// 记录事件到信息区域
     ///
-    fn draw_help_msg(&self) {
-        let mut help_msg = "Press <Ctrl+h> for help; Press <Ctrl+q> to exit".to_string();
-        let width = self.size.width;
-        let len = help_msg.len();
-        #[allow(clippy::integer_division)]
-        let padding = (width.saturating_sub(len)) / 2;
-        let spaces = " ".repeat(padding.saturating_sub(1));
-        help_msg = format!("~{spaces}{help_msg}");
-        help_msg.truncate(width);
-        let _ = Terminal::print(&help_msg);
+    /// # 参数
+    /// - `tag`: 事件分类标签（如 "INFO"）
+    /// - `info`: 事件详细信息
+    ///
+    /// 事件会记录到日志历史并显示在信息区域, 被屏蔽的标签只记录不显示
+    pub fn log_event(&mut self, tag: &str, info: &str) {
+        self.log.push(tag, info);
+    }
+
+    /// 在光标处插入粘贴的文本, 光标移动到文本之后
+    fn paste(&mut self, text: &str) {
+        if self.buffer.read_only {
+            self.log_event("EDIT", "Buffer is read-only, paste ignored.");
+            return;
+        }
+        // 按统一换行之后实际插入的字符计数
+        let text = buffer::normalize_newlines(text);
+        let Some((end, change)) = self.buffer.insert_text(self.grapheme_location, &text) else {
+            return;
+        };
+        self.shift_locations(&change);
+        self.set_grapheme_location(end);
+        self.log_event(
+            "EDIT",
+            &format!(
+                "Pasted {} chars, {} lines",
+                text.chars().count(),
+                change.inserted + 1
+            ),
+        );
+    }
+
+    /// 撤销最近一次编辑, 光标回到编辑前的位置
+    fn undo(&mut self) {
+        let Some((cursor, change)) = self.buffer.undo() else {
+            self.log_event("EDIT", "Already at oldest change.");
+            return;
+        };
+        self.shift_locations(&change);
+        let target = self.clamp_grapheme_location(cursor);
+        self.set_grapheme_location(target);
+        self.log_event("EDIT", &format!("Undo, Grapheme_Location: {target}"));
     }
 
-    /// 绘制终端过小警告
-    fn draw_size_warning(&self) {
-        const WARNING_MSG: &str = "终端尺寸过小，建议调整窗口大小";
-        for row in 0..self.size.height {
-            let _ = Terminal::clear_line();
-            if row == 0 {
-                let _ = Terminal::print(WARNING_MSG);
+    /// 按编辑造成的行变化平移标记和跳转列表
+    fn shift_locations(&mut self, change: &LineChange) {
+        let LineChange {
+            line_index,
+            removed,
+            inserted,
+        } = *change;
+        self.marks.shift_lines(line_index, removed, inserted);
+        self.jump_list.shift_lines(line_index, removed, inserted);
+        // 显示同一缓冲区的其他窗口
+        for state in self.windows.iter_mut().flatten() {
+            if state.buffer == self.active {
+                marks::shift_location(&mut state.grapheme_location, line_index, removed, inserted);
             }
-            let _ = Terminal::move_cursor_to(Position {
-                x: 0,
-                y: min(row + 1, self.size.height - 1),
-            });
         }
     }
 
-    /// 记录事件到信息区域
+    /// 处理日志查看器中的操作
+    fn handle_log_command(&mut self, command: LogCommand) {
+        let page_height = self.size.height.saturating_sub(1);
+        let Some(log_viewer) = self.log_viewer.as_mut() else {
+            return;
+        };
+        match command {
+            LogCommand::Scroll(direction) => log_viewer.scroll(direction, &self.log, page_height),
+            LogCommand::NextFilter => log_viewer.cycle_filter(true),
+            LogCommand::PreviousFilter => log_viewer.cycle_filter(false),
+            LogCommand::Close => self.log_viewer = None,
+        }
+    }
+
+    /// 把当前缓冲区的状态移出视图, 视图中留下空缓冲区
+    fn park_buffer(&mut self) -> BufferState {
+        BufferState {
+            buffer: mem::take(&mut self.buffer),
+            grapheme_location: self.grapheme_location,
+            scroll_offset: self.scroll_offset,
+            marks: mem::take(&mut self.marks),
+            jump_list: mem::take(&mut self.jump_list),
+        }
+    }
+
+    /// 让焦点窗口改为显示指定下标的缓冲区, 光标回到该缓冲区上次的位置, 停止跟随原来的缓冲区
+    fn activate_buffer(&mut self, index: usize) {
+        if index == self.active {
+            return;
+        }
+        self.stop_follow();
+        self.buffers[self.active] = Some(self.park_buffer());
+        if let Some(state) = self.buffers[index].take() {
+            self.active = index;
+            self.unpark_buffer(state);
+        }
+    }
+
+    /// 把缓冲区状态放回视图, 成为当前缓冲区
+    fn unpark_buffer(&mut self, state: BufferState) {
+        self.buffer = state.buffer;
+        self.marks = state.marks;
+        self.jump_list = state.jump_list;
+        self.scroll_offset = state.scroll_offset;
+        self.set_grapheme_location(state.grapheme_location);
+    }
+
+    /// 指定下标的缓冲区
+    fn buffer_at(&self, index: usize) -> Option<&Buffer> {
+        if index == self.active {
+            return Some(&self.buffer);
+        }
+        self.buffers.get(index)?.as_ref().map(|state| &state.buffer)
+    }
+
+    /// 指定下标的缓冲区(可变)
+    fn buffer_at_mut(&mut self, index: usize) -> Option<&mut Buffer> {
+        if index == self.active {
+            return Some(&mut self.buffer);
+        }
+        self.buffers
+            .get_mut(index)?
+            .as_mut()
+            .map(|state| &mut state.buffer)
+    }
+
+    /// 缓冲区选择器中显示的列表
+    fn buffer_entries(&self) -> Vec<BufferEntry> {
+        (0..self.buffers.len())
+            .filter_map(|index| {
+                let buffer = self.buffer_at(index)?;
+                Some(BufferEntry {
+                    name: buffer.name().to_string(),
+                    modified: buffer.modified,
+                    read_only: buffer.read_only,
+                    active: index == self.active,
+                    lines: buffer.lines.len(),
+                })
+            })
+            .collect()
+    }
+
+    /// 循环切换到下一个(或上一个)缓冲区
+    fn cycle_buffer(&mut self, forward: bool) {
+        let count = self.buffers.len();
+        if count <= 1 {
+            self.log_event("INFO", "Only one buffer open.");
+            return;
+        }
+        let next = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+        self.switch_buffer(next);
+    }
+
+    /// 关闭指定下标的缓冲区, 不退出编辑器
     ///
     /// # 参数
-    /// - `tag`: 事件分类标签（如 "INFO"）
-    /// - `info`: 事件详细信息
+    /// - `index`: 缓冲区下标
+    /// - `confirmed`: 是否已确认丢弃未保存的修改
     ///
-    /// 事件会显示在终端顶部的信息区域
-    pub fn log_event(&mut self, tag: &str, info: &str) {
-        let str = format!("[{tag:<4}] {info}");
-        if INFO_SECTION_SIZE == 0 {
+    /// 关闭当前缓冲区时切换到相邻的缓冲区; 关闭最后一个缓冲区时留下一个空缓冲区
+    fn close_buffer(&mut self, index: usize, confirmed: bool) {
+        let Some(buffer) = self.buffer_at(index) else {
+            return;
+        };
+        let name = buffer.name().to_string();
+        if buffer.modified && !confirmed {
+            self.confirm_close = Some(index);
+            self.log_event(
+                "INFO",
+                &format!("{name} has unsaved changes, close again to discard them."),
+            );
+            return;
+        }
+        if let Some(swap) = self
+            .buffer_at_mut(index)
+            .and_then(|buffer| buffer.swap.as_mut())
+        {
+            swap.remove();
+        }
+        if index == self.active {
+            self.stop_follow();
+        }
+        self.record_file_state(index);
+        if self.buffers.len() == 1 {
+            self.unpark_buffer(BufferState::default());
+            for state in self.windows.iter_mut().flatten() {
+                *state = WindowState {
+                    buffer: 0,
+                    grapheme_location: GraphemeLocation::default(),
+                    scroll_offset: Location::default(),
+                };
+            }
+        } else {
+            let neighbor = if index + 1 < self.buffers.len() {
+                index + 1
+            } else {
+                index - 1
+            };
+            if index == self.active {
+                self.switch_buffer(neighbor);
+            }
+            // 显示该缓冲区的其他窗口也改为显示相邻的缓冲区
+            for state in self.windows.iter_mut().flatten() {
+                if state.buffer == index {
+                    *state = WindowState {
+                        buffer: neighbor,
+                        grapheme_location: GraphemeLocation::default(),
+                        scroll_offset: Location::default(),
+                    };
+                }
+                if state.buffer > index {
+                    state.buffer -= 1;
+                }
+            }
+            self.buffers.remove(index);
+            if self.active > index {
+                self.active -= 1;
+            }
+        }
+        self.log_event("INFO", &format!("{name} closed."));
+    }
+
+    /// 处理缓冲区选择器中的操作
+    ///
+    /// # 参数
+    /// - `command`: 选择器操作
+    /// - `confirm_close`: 等待再次确认关闭的缓冲区下标
+    fn handle_picker_command(&mut self, command: PickerCommand, confirm_close: Option<usize>) {
+        let count = self.buffers.len();
+        let Some(picker) = self.buffer_picker.as_mut() else {
+            return;
+        };
+        match command {
+            PickerCommand::Up => picker.move_selection(false, count),
+            PickerCommand::Down => picker.move_selection(true, count),
+            PickerCommand::Accept => {
+                let index = picker.selected();
+                self.buffer_picker = None;
+                self.switch_buffer(index);
+            }
+            PickerCommand::Delete => {
+                let index = picker.selected();
+                self.close_buffer(index, confirm_close == Some(index));
+                let count = self.buffers.len();
+                if let Some(picker) = self.buffer_picker.as_mut() {
+                    picker.clamp(count);
+                }
+            }
+            PickerCommand::Cancel => self.buffer_picker = None,
+        }
+    }
+
+    /// 处理窗口操作
+    fn handle_window_command(&mut self, command: WindowCommand) {
+        match command {
+            WindowCommand::SplitHorizontal => self.split_window(Axis::Horizontal),
+            WindowCommand::SplitVertical => self.split_window(Axis::Vertical),
+            WindowCommand::Focus(direction) => {
+                let (windows, _) = self.window_tree.layout(self.layout().windows);
+                let cursor = self.get_cursor_position();
+                match window::neighbor(&windows, self.focused, cursor, direction) {
+                    Some(id) => self.focus_window(id),
+                    None => self.log_event("INFO", &format!("No window {direction:?}.")),
+                }
+            }
+            WindowCommand::FocusNext => {
+                let (windows, _) = self.window_tree.layout(self.layout().windows);
+                let position = windows.iter().position(|(id, _)| *id == self.focused);
+                let next = position.map_or(0, |position| (position + 1) % windows.len());
+                if let Some((id, _)) = windows.get(next) {
+                    self.focus_window(*id);
+                }
+            }
+            WindowCommand::Taller => self.resize_window(Axis::Horizontal, true),
+            WindowCommand::Shorter => self.resize_window(Axis::Horizontal, false),
+            WindowCommand::Wider => self.resize_window(Axis::Vertical, true),
+            WindowCommand::Narrower => self.resize_window(Axis::Vertical, false),
+            WindowCommand::Equalize => {
+                self.window_tree.equalize();
+                self.scroll_location_into_view();
+            }
+            WindowCommand::Close => self.close_window(),
+            WindowCommand::Only => {
+                self.window_tree = WindowTree::default();
+                self.windows = vec![None];
+                self.focused = 0;
+                self.scroll_location_into_view();
+                self.log_event("INFO", "Closed all other windows.");
+            }
+        }
+    }
+
+    /// 分割焦点窗口, 新窗口显示同一个缓冲区并获得焦点
+    fn split_window(&mut self, axis: Axis) {
+        let rect = self.focused_rect();
+        let room = match axis {
+            Axis::Horizontal => rect.height >= 2 * MIN_WINDOW_HEIGHT,
+            Axis::Vertical => rect.width > 2 * MIN_WINDOW_WIDTH,
+        };
+        if !room {
+            self.log_event("INFO", "Not enough room to split the window.");
+            return;
+        }
+        let new = self.windows.len();
+        self.windows.push(Some(self.park_window()));
+        self.window_tree.split(self.focused, new, axis);
+        self.focus_window(new);
+        self.log_event(
+            "INFO",
+            &format!("Split window {axis:?}, {} windows.", self.windows.len()),
+        );
+    }
+
+    /// 关闭焦点窗口, 焦点移到接管其区域的窗口, 缓冲区保持打开
+    fn close_window(&mut self) {
+        let closed = self.focused;
+        let Some(next) = self.window_tree.remove(closed) else {
+            self.log_event("INFO", "Cannot close the last window.");
+            return;
+        };
+        self.focus_window(next);
+        self.windows.remove(closed);
+        self.window_tree.renumber(closed);
+        if self.focused > closed {
+            self.focused -= 1;
+        }
+        self.scroll_location_into_view();
+        self.log_event(
+            "INFO",
+            &format!("Window closed, {} windows.", self.windows.len()),
+        );
+    }
+
+    /// 调整焦点窗口的高度或宽度
+    fn resize_window(&mut self, axis: Axis, grow: bool) {
+        let rect = self.layout().windows;
+        if self.window_tree.resize(self.focused, axis, grow, rect) {
+            self.scroll_location_into_view();
+        } else {
+            self.log_event("INFO", "Cannot resize the window further.");
+        }
+    }
+
+    /// 把焦点移到指定窗口, 恢复它的缓冲区、光标和滚动位置
+    fn focus_window(&mut self, id: usize) {
+        if id == self.focused {
             return;
         }
-        if self.key_events_info.len() >= INFO_SECTION_SIZE {
-            self.key_events_info.pop_front();
+        let Some(state) = self.windows.get_mut(id).and_then(Option::take) else {
+            return;
+        };
+        self.windows[self.focused] = Some(self.park_window());
+        self.focused = id;
+        self.activate_buffer(state.buffer);
+        self.scroll_offset = state.scroll_offset;
+        let target = self.clamp_grapheme_location(state.grapheme_location);
+        self.set_grapheme_location(target);
+    }
+
+    /// 焦点窗口当前的状态
+    fn park_window(&self) -> WindowState {
+        WindowState {
+            buffer: self.active,
+            grapheme_location: self.grapheme_location,
+            scroll_offset: self.scroll_offset,
         }
-        self.key_events_info.push_back(str);
+    }
+
+    /// 切换信息面板的显示/隐藏, 缓冲区随之扩展或收缩
+    fn toggle_info_panel(&mut self) {
+        let visible = self.info_panel.toggle();
+        self.scroll_location_into_view();
+        let state = if visible { "shown" } else { "hidden" };
+        self.log_event("INFO", &format!("Info panel {state}."));
     }
 
     /// 处理帮助命令
     ///
-    /// 会在`INFO`区打印`help`信息
+    /// 在日志查看器中显示按键帮助
     fn help(&mut self) {
-        let info = "Press <Ctrl+q> to quit the editor";
-        self.log_event("HELP", info);
+        self.log_viewer = Some(LogViewer::help());
+        self.log_event("HELP", "Showing key bindings, press <q> to close.");
     }
 
     /// 处理终端尺寸变化命令
@@ -346,7 +1961,7 @@ impl View {
     /// 会触发重绘并记录尺寸变化事件
     fn resize(&mut self, to: Size) {
         self.size = to;
-        self.needs_redraw_buffer = true;
+        self.scroll_location_into_view();
         self.log_event(
             "INFO",
             &format!(
@@ -364,12 +1979,11 @@ impl View {
     ///
     /// 获取字素位置,并移动,可以解决移动边界问题
     fn move_text_location(&mut self, direction: Direction) {
-        let Size { height, .. } = self.size;
         let GraphemeLocation {
             grapheme_index: mut x,
             line_index: mut y,
         } = self.get_grapheme_location();
-        let buffer_height = height - INFO_SECTION_SIZE; // buffer区高度
+        let buffer_height = self.buffer_height();
         let max_line = self.buffer.lines.len().saturating_sub(1); // 最大行索引
         match direction {
             Direction::Up => {
@@ -399,10 +2013,10 @@ impl View {
                 }
             }
             Direction::PageUp => {
-                y = y.saturating_sub(buffer_height - 1);
+                y = y.saturating_sub(buffer_height.saturating_sub(1));
             }
             Direction::PageDown => {
-                y = (y + buffer_height - 1).min(max_line);
+                y = (y + buffer_height.saturating_sub(1)).min(max_line);
             }
             Direction::Home => {
                 x = 0;
@@ -410,6 +2024,14 @@ impl View {
             Direction::End => {
                 x = self.buffer.lines.get(y).map_or(0, Line::fragment_len);
             }
+            Direction::FileStart => {
+                x = 0;
+                y = 0;
+            }
+            Direction::FileEnd => {
+                x = 0;
+                y = max_line;
+            }
         }
         // 限制Location {x, y} 不会超出一行的长度,不会超出文档的长度
         if let Some(line) = self.buffer.lines.get(y) {
@@ -418,13 +2040,16 @@ impl View {
             y = self.buffer.lines.len().saturating_sub(1);
             x = self.buffer.lines.get(y).map_or(0, Line::fragment_len);
         }
-        // 限制Location {x, y} 到字素边界
-        self.grapheme_location = GraphemeLocation {
+        let target = GraphemeLocation {
             grapheme_index: x,
             line_index: y,
         };
-        self.location = self.get_location();
-        self.scroll_location_into_view();
+        // 文件首尾跳转记录到跳转列表
+        if matches!(direction, Direction::FileStart | Direction::FileEnd) {
+            self.jump_list.push(self.grapheme_location);
+        }
+        // 限制Location {x, y} 到字素边界
+        self.set_grapheme_location(target);
         self.log_event(
             "MOVE",
             &format!(
@@ -434,37 +2059,287 @@ impl View {
         );
     }
 
+    /// 设置字素位置, 同步渲染位置、滚动偏移和括号匹配
+    fn set_grapheme_location(&mut self, grapheme_location: GraphemeLocation) {
+        self.grapheme_location = grapheme_location;
+        self.location = self.get_location();
+        self.scroll_location_into_view();
+        self.update_matched_bracket();
+    }
+
+    /// 更新光标处括号的匹配位置
+    fn update_matched_bracket(&mut self) {
+        let matched = bracket::find_matching_bracket(&self.buffer, self.grapheme_location);
+        self.matched_bracket = matched;
+    }
+
+    /// 跳转到匹配的括号
+    ///
+    /// 光标不在括号上时, 先在当前行向后查找第一个括号, 再跳到它的匹配括号
+    fn jump_to_matching_bracket(&mut self) {
+        let target = bracket::find_bracket_in_line(&self.buffer, self.grapheme_location)
+            .and_then(|bracket| bracket::find_matching_bracket(&self.buffer, bracket));
+        if let Some(target) = target {
+            self.set_grapheme_location(target);
+            self.log_event(
+                "MOVE",
+                &format!("Match bracket, Grapheme_Location: {target}"),
+            );
+        } else {
+            self.log_event("INFO", "No matching bracket found.");
+        }
+    }
+
+    /// 大幅度跳转到目标位置, 并把出发位置记录到跳转列表
+    fn jump_to(&mut self, target: GraphemeLocation) {
+        self.jump_list.push(self.grapheme_location);
+        self.set_grapheme_location(target);
+    }
+
+    /// 将位置限制在缓冲区范围内
+    ///
+    /// 标记和跳转记录可能指向已不存在的行或列
+    fn clamp_grapheme_location(&self, location: GraphemeLocation) -> GraphemeLocation {
+        let line_index = location
+            .line_index
+            .min(self.buffer.lines.len().saturating_sub(1));
+        let grapheme_index = location.grapheme_index.min(
+            self.buffer
+                .lines
+                .get(line_index)
+                .map_or(0, Line::fragment_len),
+        );
+        GraphemeLocation {
+            grapheme_index,
+            line_index,
+        }
+    }
+
+    /// 在光标处设置命名标记
+    fn set_mark(&mut self, name: char) {
+        if self.marks.set(name, self.grapheme_location) {
+            self.log_event(
+                "INFO",
+                &format!("Mark '{name}' set at {}", self.grapheme_location),
+            );
+        } else {
+            self.log_event("NSUP", &format!("Invalid mark name '{name}'"));
+        }
+    }
+
+    /// 跳转到命名标记
+    fn jump_to_mark(&mut self, name: char) {
+        if let Some(location) = self.marks.get(name) {
+            let target = self.clamp_grapheme_location(location);
+            self.jump_to(target);
+            self.log_event(
+                "MOVE",
+                &format!("Mark '{name}', Grapheme_Location: {target}"),
+            );
+        } else {
+            self.log_event("INFO", &format!("Mark '{name}' not set."));
+        }
+    }
+
+    /// 跳转列表后退
+    fn jump_back(&mut self) {
+        if let Some(location) = self.jump_list.back(self.grapheme_location) {
+            let target = self.clamp_grapheme_location(location);
+            self.set_grapheme_location(target);
+            self.log_event("MOVE", &format!("Jump back, Grapheme_Location: {target}"));
+        } else {
+            self.log_event("INFO", "Already at oldest jump.");
+        }
+    }
+
+    /// 跳转列表前进
+    fn jump_forward(&mut self) {
+        if let Some(location) = self.jump_list.forward() {
+            let target = self.clamp_grapheme_location(location);
+            self.set_grapheme_location(target);
+            self.log_event(
+                "MOVE",
+                &format!("Jump forward, Grapheme_Location: {target}"),
+            );
+        } else {
+            self.log_event("INFO", "Already at newest jump.");
+        }
+    }
+
+    /// 按当前尺寸和设置计算的界面布局, 分割窗口后不显示全局状态栏
+    fn layout(&self) -> Layout {
+        Layout::compute(
+            self.size,
+            self.info_panel.rows(),
+            self.info_panel.position(),
+            !self.window_tree.is_split(),
+        )
+    }
+
+    /// 焦点窗口的区域
+    fn focused_rect(&self) -> Rect {
+        let (windows, _) = self.window_tree.layout(self.layout().windows);
+        windows
+            .into_iter()
+            .find(|(id, _)| *id == self.focused)
+            .map_or_else(Rect::default, |(_, rect)| rect)
+    }
+
+    /// 焦点窗口内的布局
+    fn pane(&self) -> Pane {
+        Pane::compute(
+            self.focused_rect(),
+            self.gutter_width(&self.buffer),
+            self.windows.len() > 1,
+        )
+    }
+
+    /// 行号栏希望占用的宽度: 最大行号的位数加一个空格, 不显示行号时为 0
+    fn gutter_width(&self, buffer: &Buffer) -> usize {
+        if self.line_numbers {
+            buffer.lines.len().max(1).to_string().len() + 1
+        } else {
+            0
+        }
+    }
+
+    /// 焦点窗口文本区高度
+    fn buffer_height(&self) -> usize {
+        self.pane().height
+    }
+
+    /// 实际生效的滚动边距
+    ///
+    /// 边距不能超过buffer区高度的一半, 否则光标无处可放
+    fn effective_scroll_off(&self) -> usize {
+        #[allow(clippy::integer_division)]
+        let max_scroll_off = self.buffer_height().saturating_sub(1) / 2;
+        self.scroll_off.min(max_scroll_off)
+    }
+
+    /// 滚动视图而不移动光标
+    ///
+    /// 只有光标离开可见区域(含滚动边距)时才把它带回视图内
+    fn scroll_view(&mut self, scroll: Scroll) {
+        let buffer_height = self.buffer_height();
+        let max_line = self.buffer.lines.len().saturating_sub(1);
+        #[allow(clippy::integer_division)]
+        let half_page = (buffer_height / 2).max(1);
+        let top_row = self.scroll_offset.y;
+        let new_top_row = match scroll {
+            Scroll::LineUp => top_row.saturating_sub(1),
+            Scroll::LineDown => top_row.saturating_add(1).min(max_line),
+            Scroll::HalfPageUp => top_row.saturating_sub(half_page),
+            Scroll::HalfPageDown => top_row.saturating_add(half_page).min(max_line),
+        };
+        if new_top_row == top_row {
+            return;
+        }
+        self.scroll_offset.y = new_top_row;
+
+        // 把光标限制在新的可见区域内
+        let scroll_off = self.effective_scroll_off();
+        let min_y = if new_top_row == 0 {
+            0
+        } else {
+            new_top_row + scroll_off
+        };
+        let bottom_row = (new_top_row + buffer_height).saturating_sub(1);
+        let max_y = if bottom_row >= max_line {
+            max_line
+        } else {
+            bottom_row.saturating_sub(scroll_off)
+        };
+        let y = self.grapheme_location.line_index.max(min_y).min(max_y);
+        if y != self.grapheme_location.line_index {
+            let target = self.clamp_grapheme_location(GraphemeLocation {
+                grapheme_index: self.grapheme_location.grapheme_index,
+                line_index: y,
+            });
+            self.set_grapheme_location(target);
+        }
+        self.log_event(
+            "MOVE",
+            &format!("Scroll {scroll:?}, top row {}", self.scroll_offset.y + 1),
+        );
+    }
+
+    /// 将光标行重新定位到视图顶部/中部/底部
+    fn recenter(&mut self, recenter: Recenter) {
+        let buffer_height = self.buffer_height();
+        let scroll_off = self.effective_scroll_off();
+        let y = self.grapheme_location.line_index;
+        self.scroll_offset.y = match recenter {
+            Recenter::Top => y.saturating_sub(scroll_off),
+            #[allow(clippy::integer_division)]
+            Recenter::Middle => y.saturating_sub(buffer_height / 2),
+            Recenter::Bottom => (y + scroll_off + 1).saturating_sub(buffer_height),
+        };
+        self.log_event(
+            "MOVE",
+            &format!(
+                "Recenter {recenter:?}, top row {}",
+                self.scroll_offset.y + 1
+            ),
+        );
+    }
+
     fn scroll_location_into_view(&mut self) {
         let Location { x, y } = self.location;
-        let Size { width, height } = self.size;
-        let mut offset_changed = false;
+        let width = self.pane().text_width;
         // 如果text location在screen外, 需要滚动
         // 水平滚动
         if x < self.scroll_offset.x {
             self.scroll_offset.x = x;
-            offset_changed = true;
         } else if x >= self.scroll_offset.x.saturating_add(width) {
             self.scroll_offset.x = x.saturating_sub(width).saturating_add(1);
-            offset_changed = true;
         }
-        // 垂直滚动
-        let buffer_height = height - INFO_SECTION_SIZE;
-        if y < self.scroll_offset.y {
-            self.scroll_offset.y = y;
-            offset_changed = true;
-        } else if y >= self.scroll_offset.y.saturating_add(buffer_height) {
-            self.scroll_offset.y = y.saturating_sub(buffer_height).saturating_add(1);
-            offset_changed = true;
+        // 垂直滚动, 光标上下保留 scroll_off 行的边距(文件首尾除外)
+        let buffer_height = self.buffer_height();
+        let scroll_off = self.effective_scroll_off();
+        let max_line = self.buffer.lines.len().saturating_sub(1);
+        let top = y.saturating_sub(scroll_off);
+        let bottom = y.saturating_add(scroll_off).min(max_line.max(y));
+        if top < self.scroll_offset.y {
+            self.scroll_offset.y = top;
+        } else if bottom >= self.scroll_offset.y.saturating_add(buffer_height) {
+            self.scroll_offset.y = bottom.saturating_sub(buffer_height).saturating_add(1);
         }
-
-        self.needs_redraw_buffer = offset_changed;
     }
 
-    fn handle_other_key_command(&mut self, str: &str) {
-        self.log_event("KEY", str);
+    /// 以 `KEY` 标签记录按键松开事件
+    fn handle_key_release(&mut self, key_event: KeyEvent) {
+        self.log_event(
+            "KEY",
+            &format!(
+                "Release: code={},modifiers={},state={:?}",
+                key_event.code, key_event.modifiers, key_event.state
+            ),
+        );
     }
 
     fn handle_other_event(&mut self, str: &str) {
         self.log_event("OTH", str);
     }
 }
+
+/// 命令是否会让光标或视图离开跟随的位置(手动移动、滚动或切换到其他缓冲区)
+fn stops_follow(command: &EditorCommand) -> bool {
+    matches!(
+        command,
+        EditorCommand::Move(_)
+            | EditorCommand::Scroll(_)
+            | EditorCommand::Recenter(_)
+            | EditorCommand::MatchBracket
+            | EditorCommand::JumpToMark(_)
+            | EditorCommand::JumpBack
+            | EditorCommand::JumpForward
+            | EditorCommand::NextBuffer
+            | EditorCommand::PreviousBuffer
+            | EditorCommand::CloseBuffer
+            | EditorCommand::Picker(PickerCommand::Accept)
+            | EditorCommand::Window(
+                WindowCommand::Focus(_) | WindowCommand::FocusNext | WindowCommand::Close
+            )
+    )
+}
@@ -0,0 +1,142 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 09:40:12
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 09:40:12
+ * @FilePath: \rim\src\editor\view\bracket.rs
+ * @Description: 括号匹配
+ */
+//! 括号匹配模块
+//!
+//! 支持 `()`、`[]`、`{}` 三种括号的跨行匹配,
+//! 有语法定义时跳过字符串和注释中的括号
+
+use super::buffer::Buffer;
+use super::syntax::TokenKind;
+use super::GraphemeLocation;
+
+/// 支持匹配的括号对
+const BRACKET_PAIRS: [(&str, &str); 3] = [("(", ")"), ("[", "]"), ("{", "}")];
+
+/// 括号方向
+enum Bracket {
+    /// 左括号, 向后查找
+    Open(&'static str, &'static str),
+    /// 右括号, 向前查找
+    Close(&'static str, &'static str),
+}
+
+impl Bracket {
+    fn from_grapheme(grapheme: &str) -> Option<Self> {
+        BRACKET_PAIRS.iter().find_map(|&(open, close)| {
+            if grapheme == open {
+                Some(Self::Open(open, close))
+            } else if grapheme == close {
+                Some(Self::Close(open, close))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// 获取指定位置的代码括号(字符串和注释中的括号不计)
+fn bracket_at(buffer: &Buffer, at: GraphemeLocation) -> Option<Bracket> {
+    let line = buffer.lines.get(at.line_index)?;
+    if buffer.token_kind(at.line_index, at.grapheme_index) != TokenKind::Code {
+        return None;
+    }
+    Bracket::from_grapheme(line.grapheme(at.grapheme_index)?)
+}
+
+/// 查找 `at` 处括号的匹配括号
+///
+/// # 返回
+/// `at` 处不是括号或括号未闭合时返回 `None`
+pub fn find_matching_bracket(buffer: &Buffer, at: GraphemeLocation) -> Option<GraphemeLocation> {
+    match bracket_at(buffer, at)? {
+        Bracket::Open(open, close) => search_forward(buffer, at, open, close),
+        Bracket::Close(open, close) => search_backward(buffer, at, open, close),
+    }
+}
+
+/// 从 `at` 开始(含)在当前行向后查找第一个代码括号
+///
+/// 用于光标不在括号上时的跳转, 与 vim 的 `%` 行为一致
+pub fn find_bracket_in_line(buffer: &Buffer, at: GraphemeLocation) -> Option<GraphemeLocation> {
+    let line = buffer.lines.get(at.line_index)?;
+    (at.grapheme_index..line.fragment_len())
+        .map(|grapheme_index| GraphemeLocation {
+            grapheme_index,
+            line_index: at.line_index,
+        })
+        .find(|location| bracket_at(buffer, *location).is_some())
+}
+
+fn search_forward(
+    buffer: &Buffer,
+    at: GraphemeLocation,
+    open: &str,
+    close: &str,
+) -> Option<GraphemeLocation> {
+    let mut depth = 0usize;
+    for (line_index, line) in buffer.lines.iter().enumerate().skip(at.line_index) {
+        let start = if line_index == at.line_index {
+            at.grapheme_index + 1
+        } else {
+            0
+        };
+        for (grapheme_index, grapheme) in line.graphemes().enumerate().skip(start) {
+            if buffer.token_kind(line_index, grapheme_index) != TokenKind::Code {
+                continue;
+            }
+            if grapheme == open {
+                depth += 1;
+            } else if grapheme == close {
+                if depth == 0 {
+                    return Some(GraphemeLocation {
+                        grapheme_index,
+                        line_index,
+                    });
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+fn search_backward(
+    buffer: &Buffer,
+    at: GraphemeLocation,
+    open: &str,
+    close: &str,
+) -> Option<GraphemeLocation> {
+    let mut depth = 0usize;
+    for line_index in (0..=at.line_index).rev() {
+        let line = buffer.lines.get(line_index)?;
+        let end = if line_index == at.line_index {
+            at.grapheme_index
+        } else {
+            line.fragment_len()
+        };
+        for grapheme_index in (0..end).rev() {
+            if buffer.token_kind(line_index, grapheme_index) != TokenKind::Code {
+                continue;
+            }
+            let grapheme = line.grapheme(grapheme_index)?;
+            if grapheme == close {
+                depth += 1;
+            } else if grapheme == open {
+                if depth == 0 {
+                    return Some(GraphemeLocation {
+                        grapheme_index,
+                        line_index,
+                    });
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
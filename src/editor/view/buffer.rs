@@ -8,13 +8,106 @@
  */
 //! 文本缓冲区模块
 //!
-//! 提供文件加载和文本行存储功能
+//! 提供文件加载、文本行存储和可撤销的编辑功能, 内容也可以来自标准输入并逐批追加,
+//! 或在文件增长时读取新追加的部分; 加载时记录文件的修改时间、大小和哈希, 用于发现外部修改
 //!
 //! 主要结构：
-//! - [`Buffer`]: 存储文本行并提供基本操作
+//! - [`Buffer`] - 存储文本行并提供基本操作
+//! - [`LineChange`] - 编辑造成的行范围变化, 用于平移标记等位置
 
+use super::explorer::Explorer;
 use super::line::Line;
-use std::{fs::read_to_string, io::Error};
+use super::swap::SwapFile;
+use super::syntax::{ScanState, Syntax, TokenKind};
+use super::GraphemeLocation;
+use std::fmt;
+use std::{
+    fs::{self, metadata, read_to_string, File, Metadata},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{Error, Read, Seek, SeekFrom},
+    time::SystemTime,
+};
+
+/// UTF-8 字节顺序标记
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// 换行符类型
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lf => write!(f, "LF"),
+            Self::CrLf => write!(f, "CRLF"),
+        }
+    }
+}
+
+/// 编辑造成的行范围变化
+///
+/// 在 `line_index` 处删除了 `removed` 行并插入了 `inserted` 行
+pub struct LineChange {
+    pub line_index: usize,
+    pub removed: usize,
+    pub inserted: usize,
+}
+
+/// 再次读取文件时文件大小的变化
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileGrowth {
+    /// 没有新内容
+    Unchanged,
+    /// 读取了新追加的内容
+    Appended,
+    /// 文件比已读取的部分短(被截断或替换), 需要重新加载
+    Truncated,
+}
+
+/// 文件在磁盘上的状态
+///
+/// 修改时间或大小变化时才读取文件比较哈希, 只是被 `touch` 过的文件不算修改
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DiskStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl DiskStamp {
+    fn new(meta: &Metadata, contents: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        Self {
+            modified: meta.modified().ok(),
+            len: meta.len(),
+            hash: hasher.finish(),
+        }
+    }
+
+    /// 修改时间和大小是否与 `meta` 相同
+    fn matches(&self, meta: &Metadata) -> bool {
+        self.modified == meta.modified().ok() && self.len == meta.len()
+    }
+}
+
+/// 一次可撤销的编辑
+///
+/// 记录被替换的原始行, 撤销时整体换回
+struct Edit {
+    /// 第一个被替换的行号
+    line_index: usize,
+    /// 被替换的原始行
+    old_lines: Vec<String>,
+    /// 替换后的行数
+    new_line_count: usize,
+    /// 编辑前的光标位置
+    cursor: GraphemeLocation,
+}
 
 /// 文本缓冲区
 ///
@@ -23,6 +116,49 @@ use std::{fs::read_to_string, io::Error};
 pub struct Buffer {
     /// 文本行存储向量
     pub lines: Vec<Line>,
+    /// 文件对应的语法定义, 未识别的文件类型为 `None`
+    pub syntax: Option<&'static Syntax>,
+    /// 每行每个字素的词法类别, 与 `lines` 一一对应
+    token_kinds: Vec<Vec<TokenKind>>,
+    /// 每行末尾的跨行扫描状态, 与 `token_kinds` 一一对应; 追加行时从最后一行的状态继续扫描
+    scan_states: Vec<ScanState>,
+    /// 文件路径, 未关联文件时为 `None`
+    pub file_name: Option<String>,
+    /// 是否有未保存的修改
+    pub modified: bool,
+    /// 文件是否只读
+    pub read_only: bool,
+    /// 文件使用的换行符
+    pub line_ending: LineEnding,
+    /// 文件是否以 UTF-8 BOM 开头
+    pub has_bom: bool,
+    /// 撤销栈, 最近的编辑在最后
+    undo_stack: Vec<Edit>,
+    /// 内容来自标准输入时为读取状态; 这样的缓冲区没有对应的文件, 保存时需要指定文件名
+    pub stdin: Option<StdinState>,
+    /// 已从文件读取的字节数
+    read_len: u64,
+    /// 最后一个换行符之后的字节偏移, 之后是还没有结束的最后一行
+    tail_offset: u64,
+    /// 上次加载(或确认保留缓冲区内容)时文件在磁盘上的状态
+    disk_stamp: Option<DiskStamp>,
+    /// 没有对应文件的缓冲区显示的名称, 如差异
+    title: Option<String>,
+    /// 编辑计数, 每次编辑或撤销加一, 用于判断交换文件是否需要更新
+    pub edits: u64,
+    /// 交换文件, 没有对应文件或文件正被其他进程编辑时为 `None`
+    pub swap: Option<SwapFile>,
+    /// 目录浏览缓冲区显示的目录, 每行对应一个条目
+    pub explorer: Option<Explorer>,
+}
+
+/// 标准输入缓冲区的读取状态
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StdinState {
+    /// 仍在读取, 新到达的行会追加到末尾
+    Reading,
+    /// 输入已结束
+    Finished,
 }
 
 impl Buffer {
@@ -38,16 +174,355 @@ impl Buffer {
     /// 返回 `std::io::Error` 如果文件读取失败
     ///
     /// # 示例
-    /// ```no_run
+    /// ```ignore
     /// let buffer = Buffer::load_file("example.txt")?;
     /// ```
     pub fn load_file(filename: &str) -> Result<Self, Error> {
         let file_contents = read_to_string(filename)?;
+        let read_len = u64::try_from(file_contents.len()).unwrap_or(u64::MAX);
+        let tail_offset = file_contents
+            .rfind('\n')
+            .map_or(0, |index| u64::try_from(index + 1).unwrap_or(u64::MAX));
+        let has_bom = file_contents.starts_with('\u{feff}');
+        let file_contents = file_contents.trim_start_matches('\u{feff}');
         let mut lines = Vec::new();
         for line_str in file_contents.lines() {
             lines.push(Line::from(line_str));
         }
-        Ok(Self { lines })
+        let line_ending = if file_contents.contains("\r\n") {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        };
+        let meta = metadata(filename);
+        let read_only = meta
+            .as_ref()
+            .is_ok_and(|meta| meta.permissions().readonly());
+        let disk_stamp = meta
+            .ok()
+            .map(|meta| DiskStamp::new(&meta, file_contents.as_bytes()));
+        let mut buffer = Self {
+            lines,
+            syntax: Syntax::detect(filename),
+            token_kinds: Vec::new(),
+            scan_states: Vec::new(),
+            file_name: Some(filename.to_string()),
+            modified: false,
+            read_only,
+            line_ending,
+            has_bom,
+            undo_stack: Vec::new(),
+            stdin: None,
+            read_len,
+            tail_offset,
+            disk_stamp,
+            title: None,
+            edits: 0,
+            swap: None,
+            explorer: None,
+        };
+        buffer.update_token_kinds();
+        Ok(buffer)
+    }
+
+    /// 创建接收标准输入的空缓冲区, 内容随后由 [`Buffer::append_lines`] 追加
+    pub fn from_stdin() -> Self {
+        Self {
+            stdin: Some(StdinState::Reading),
+            ..Self::default()
+        }
+    }
+
+    /// 创建没有对应文件的只读缓冲区
+    ///
+    /// # 参数
+    /// - `title`: 显示的名称
+    /// - `lines`: 内容
+    pub fn scratch(title: String, lines: Vec<String>) -> Self {
+        let mut buffer = Self {
+            read_only: true,
+            title: Some(title),
+            ..Self::default()
+        };
+        buffer.append_lines(lines);
+        buffer
+    }
+
+    /// 创建显示目录内容的只读缓冲区
+    pub fn directory(explorer: Explorer) -> Self {
+        let mut buffer = Self::scratch(explorer.title(), explorer.lines());
+        buffer.explorer = Some(explorer);
+        buffer
+    }
+
+    /// 是否仍在读取标准输入
+    pub fn is_streaming(&self) -> bool {
+        self.stdin == Some(StdinState::Reading)
+    }
+
+    /// 在末尾追加行, 不记录撤销
+    ///
+    /// 行尾的 `\r` 会被去掉, 并把换行符记为 CRLF; 只计算新行的词法类别
+    pub fn append_lines(&mut self, lines: Vec<String>) {
+        let start = self.lines.len();
+        for mut line in lines {
+            if line.ends_with('\r') {
+                line.pop();
+                self.line_ending = LineEnding::CrLf;
+            }
+            self.lines.push(Line::from(line.as_str()));
+        }
+        self.update_token_kinds_from(start);
+    }
+
+    /// 读取文件末尾新追加的内容
+    ///
+    /// 最后一行没有换行符时, 每次都重新读取这一行, 让它随文件一起增长
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果文件读取失败
+    pub fn read_appended(&mut self) -> Result<FileGrowth, Error> {
+        let Some(file_name) = &self.file_name else {
+            return Ok(FileGrowth::Unchanged);
+        };
+        let mut file = File::open(file_name)?;
+        let len = file.metadata()?.len();
+        if len < self.read_len {
+            return Ok(FileGrowth::Truncated);
+        }
+        if len == self.read_len {
+            return Ok(FileGrowth::Unchanged);
+        }
+        file.seek(SeekFrom::Start(self.tail_offset))?;
+        let mut bytes = Vec::new();
+        file.take(len - self.tail_offset).read_to_end(&mut bytes)?;
+        let complete = bytes
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |index| index + 1);
+        // 上次读到的不完整的最后一行会连同新内容一起重新读取
+        if self.read_len > self.tail_offset {
+            self.lines.pop();
+            self.token_kinds.pop();
+            self.scan_states.pop();
+        }
+        // 字节顺序标记只可能出现在文件开头
+        let text = if self.tail_offset == 0 {
+            bytes.strip_prefix(BOM).unwrap_or(&bytes)
+        } else {
+            &bytes
+        };
+        self.read_len = self.tail_offset + u64::try_from(bytes.len()).unwrap_or(u64::MAX);
+        self.tail_offset += u64::try_from(complete).unwrap_or(u64::MAX);
+        let mut lines: Vec<String> = text
+            .split(|&byte| byte == b'\n')
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect();
+        if lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+        self.append_lines(lines);
+        Ok(FileGrowth::Appended)
+    }
+
+    /// 文件在磁盘上的内容是否与上次加载时不同
+    ///
+    /// 文件不存在或无法读取时不算修改; 内容相同时只更新记录的修改时间和大小
+    pub fn changed_on_disk(&mut self) -> bool {
+        let (Some(file_name), Some(stamp)) = (&self.file_name, &self.disk_stamp) else {
+            return false;
+        };
+        let Ok(meta) = metadata(file_name) else {
+            return false;
+        };
+        if stamp.matches(&meta) {
+            return false;
+        }
+        let Ok(contents) = fs::read(file_name) else {
+            return false;
+        };
+        let current = DiskStamp::new(&meta, &contents);
+        if current.hash == stamp.hash {
+            self.disk_stamp = Some(current);
+            return false;
+        }
+        true
+    }
+
+    /// 保留缓冲区的内容, 把磁盘上的当前状态记为已知, 直到文件再次变化
+    pub fn keep_disk_version(&mut self) {
+        let Some(file_name) = &self.file_name else {
+            return;
+        };
+        if let (Ok(meta), Ok(contents)) = (metadata(file_name), fs::read(file_name)) {
+            self.disk_stamp = Some(DiskStamp::new(&meta, &contents));
+        }
+    }
+
+    /// 显示用的名称: 文件路径、标题, 或 `[stdin]` / `[No Name]`
+    pub fn name(&self) -> &str {
+        if let Some(name) = self.file_name.as_deref().or(self.title.as_deref()) {
+            name
+        } else if self.stdin.is_some() {
+            "[stdin]"
+        } else {
+            "[No Name]"
+        }
+    }
+
+    /// 重新计算所有行的词法类别
+    fn update_token_kinds(&mut self) {
+        self.update_token_kinds_from(0);
+    }
+
+    /// 重新计算从 `start` 行开始的词法类别, 之前的行保持不变
+    fn update_token_kinds_from(&mut self, start: usize) {
+        self.token_kinds.truncate(start);
+        self.scan_states.truncate(start);
+        let mut state = self.scan_states.last().copied().unwrap_or_default();
+        for line in &self.lines[self.token_kinds.len()..] {
+            let kinds = match self.syntax {
+                Some(syntax) => syntax.classify(line, &mut state),
+                None => vec![TokenKind::Code; line.fragment_len()],
+            };
+            self.token_kinds.push(kinds);
+            self.scan_states.push(state);
+        }
+    }
+
+    /// 在指定位置插入文本, 作为一次可撤销的编辑
+    ///
+    /// 文本中的 `\r\n` 和 `\r` 统一为换行, 多行文本会拆分为多行
+    ///
+    /// # 参数
+    /// - `at`: 插入位置
+    /// - `text`: 插入的文本
+    ///
+    /// # 返回
+    /// 插入文本之后的位置和行范围变化, 文本为空时返回 `None`
+    pub fn insert_text(
+        &mut self,
+        at: GraphemeLocation,
+        text: &str,
+    ) -> Option<(GraphemeLocation, LineChange)> {
+        let text = normalize_newlines(text);
+        if text.is_empty() {
+            return None;
+        }
+        let line_index = at.line_index.min(self.lines.len());
+        let old_lines: Vec<String> = self
+            .lines
+            .get(line_index)
+            .map(|line| vec![line.as_str().to_string()])
+            .unwrap_or_default();
+        let byte_index = self
+            .lines
+            .get(line_index)
+            .map_or(0, |line| line.byte_index(at.grapheme_index));
+        let (prefix, suffix) = old_lines
+            .first()
+            .map_or(("", ""), |line| line.split_at(byte_index));
+
+        let mut new_lines: Vec<String> = text.split('\n').map(String::from).collect();
+        if let Some(first) = new_lines.first_mut() {
+            first.insert_str(0, prefix);
+        }
+        let mut end = GraphemeLocation {
+            grapheme_index: 0,
+            line_index: line_index + new_lines.len() - 1,
+        };
+        if let Some(last) = new_lines.last_mut() {
+            end.grapheme_index = Line::from(last).fragment_len();
+            last.push_str(suffix);
+        }
+
+        let change = LineChange {
+            line_index: line_index + 1,
+            removed: old_lines.len().saturating_sub(1),
+            inserted: new_lines.len().saturating_sub(1),
+        };
+        let replaced = line_index..line_index + old_lines.len();
+        self.lines
+            .splice(replaced, new_lines.iter().map(|line| Line::from(line)));
+        self.undo_stack.push(Edit {
+            line_index,
+            old_lines,
+            new_line_count: new_lines.len(),
+            cursor: at,
+        });
+        self.modified = true;
+        self.edits += 1;
+        self.update_token_kinds();
+        Some((end, change))
+    }
+
+    /// 用 `lines` 替换全部内容, 作为一次可撤销的编辑
+    ///
+    /// # 参数
+    /// - `lines`: 新内容
+    /// - `cursor`: 编辑前的光标位置, 撤销时恢复
+    pub fn replace_lines(&mut self, lines: &[String], cursor: GraphemeLocation) {
+        let old_lines = self
+            .lines
+            .iter()
+            .map(|line| line.as_str().to_string())
+            .collect();
+        self.undo_stack.push(Edit {
+            line_index: 0,
+            old_lines,
+            new_line_count: lines.len(),
+            cursor,
+        });
+        self.lines = lines.iter().map(|line| Line::from(line)).collect();
+        self.modified = true;
+        self.edits += 1;
+        self.update_token_kinds();
+    }
+
+    /// 撤销最近一次编辑
+    ///
+    /// 撤销所有编辑后缓冲区恢复为未修改状态
+    ///
+    /// # 返回
+    /// 编辑前的光标位置和行范围变化, 没有可撤销的编辑时返回 `None`
+    pub fn undo(&mut self) -> Option<(GraphemeLocation, LineChange)> {
+        let edit = self.undo_stack.pop()?;
+        let end = (edit.line_index + edit.new_line_count).min(self.lines.len());
+        let change = LineChange {
+            line_index: edit.line_index + 1,
+            removed: edit.new_line_count.saturating_sub(1),
+            inserted: edit.old_lines.len().saturating_sub(1),
+        };
+        self.lines.splice(
+            edit.line_index..end,
+            edit.old_lines.iter().map(|line| Line::from(line)),
+        );
+        self.modified = !self.undo_stack.is_empty();
+        self.edits += 1;
+        self.update_token_kinds();
+        Some((edit.cursor, change))
+    }
+
+    /// 获取指定位置字素的词法类别
+    ///
+    /// 超出范围时视为代码
+    pub fn token_kind(&self, line_index: usize, grapheme_index: usize) -> TokenKind {
+        self.token_kinds
+            .get(line_index)
+            .and_then(|kinds| kinds.get(grapheme_index))
+            .copied()
+            .unwrap_or(TokenKind::Code)
+    }
+
+    /// 文件编码名称
+    ///
+    /// 文件按 UTF-8 读取, 只区分是否带 BOM
+    pub fn encoding(&self) -> &'static str {
+        if self.has_bom {
+            "utf-8-bom"
+        } else {
+            "utf-8"
+        }
     }
 
     /// 检查缓冲区是否为空
@@ -58,3 +533,68 @@ impl Buffer {
         self.lines.is_empty()
     }
 }
+
+/// 把文本中的 `\r\n` 和 `\r` 统一为 `\n`
+pub fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    /// 把 `lines` 一次性放入缓冲区时的词法类别
+    fn classified(syntax: &'static Syntax, lines: &[&str]) -> Vec<Vec<TokenKind>> {
+        let mut buffer = Buffer {
+            syntax: Some(syntax),
+            lines: lines.iter().map(|line| Line::from(line)).collect(),
+            ..Buffer::default()
+        };
+        buffer.update_token_kinds();
+        buffer.token_kinds
+    }
+
+    #[test]
+    fn appended_lines_continue_scan_state() {
+        let syntax = Syntax::detect("a.rs").expect("rust syntax");
+        let lines = [
+            "let a = 1; /* open",
+            "still comment",
+            "*/ let s = \"x",
+            "y\";",
+        ];
+        let mut buffer = Buffer {
+            syntax: Some(syntax),
+            ..Buffer::default()
+        };
+        for line in lines {
+            buffer.append_lines(vec![line.to_string()]);
+        }
+        assert_eq!(buffer.token_kinds, classified(syntax, &lines));
+        assert_eq!(buffer.scan_states.len(), lines.len());
+        assert_eq!(buffer.token_kind(1, 0), TokenKind::Comment);
+    }
+
+    #[test]
+    fn read_appended_reclassifies_partial_last_line() {
+        let path = env::temp_dir().join(format!("rim-buffer-{}-append.rs", std::process::id()));
+        fs::write(&path, "let a = 1;\n/* open").expect("write file");
+        let name = path.to_string_lossy().into_owned();
+        let mut buffer = Buffer::load_file(&name).expect("load file");
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(b" */ fn f() {}\n\"s\"\n"))
+            .expect("append");
+        assert!(matches!(buffer.read_appended(), Ok(FileGrowth::Appended)));
+        let reloaded = Buffer::load_file(&name).expect("reload file");
+        let _ = fs::remove_file(&path);
+        assert_eq!(buffer.lines.len(), 3);
+        assert_eq!(buffer.token_kinds, reloaded.token_kinds);
+        assert_eq!(buffer.scan_states, reloaded.scan_states);
+        assert_eq!(buffer.token_kind(2, 0), TokenKind::String);
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 19:48:21
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 19:48:21
+ * @FilePath: \rim\src\editor\view\bufferlist.rs
+ * @Description: 打开的缓冲区列表与缓冲区选择器
+ */
+//! 缓冲区列表模块
+//!
+//! - [`BufferState`] - 非活动缓冲区连同它的光标、滚动位置、标记和跳转列表
+//! - [`BufferPicker`] - 全屏列出所有打开的缓冲区, 可以切换或关闭
+
+use super::buffer::Buffer;
+use super::location::Location;
+use super::marks::{JumpList, Marks};
+use super::GraphemeLocation;
+use crate::editor::frame::Frame;
+use crate::editor::terminal::{Position, Size};
+use crate::editor::theme::Theme;
+
+/// 非活动缓冲区的完整状态, 切换回来时原样恢复
+#[derive(Default)]
+pub struct BufferState {
+    pub buffer: Buffer,
+    pub grapheme_location: GraphemeLocation,
+    pub scroll_offset: Location,
+    pub marks: Marks,
+    pub jump_list: JumpList,
+}
+
+/// 选择器中的一项
+pub struct BufferEntry {
+    /// 显示的文件名
+    pub name: String,
+    /// 是否有未保存的修改
+    pub modified: bool,
+    /// 是否只读
+    pub read_only: bool,
+    /// 是否为当前缓冲区
+    pub active: bool,
+    /// 总行数
+    pub lines: usize,
+}
+
+/// 缓冲区选择器状态
+#[derive(Default)]
+pub struct BufferPicker {
+    /// 选中项的下标
+    selected: usize,
+}
+
+impl BufferPicker {
+    /// 创建选择器, 初始选中指定项(通常为当前缓冲区)
+    pub fn new(selected: usize) -> Self {
+        Self { selected }
+    }
+
+    /// 选中项的下标
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// 上下移动选中项, 不会越过列表首尾
+    ///
+    /// # 参数
+    /// - `down`: `true` 向下移动
+    /// - `count`: 列表项数
+    pub fn move_selection(&mut self, down: bool, count: usize) {
+        self.selected = if down {
+            (self.selected + 1).min(count.saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    /// 列表项数变化后把选中项限制在范围内
+    pub fn clamp(&mut self, count: usize) {
+        self.selected = self.selected.min(count.saturating_sub(1));
+    }
+
+    /// 选中项所在的屏幕行, 供放置光标; 终端只有一行(没有列表区域)时为标题栏
+    pub fn cursor_row(&self, size: Size) -> usize {
+        let page_height = size.height.saturating_sub(1);
+        // 没有列表区域时 `top` 越过了选中项
+        (self.selected + 1)
+            .saturating_sub(self.top(page_height))
+            .min(size.height.saturating_sub(1))
+    }
+
+    /// 第一行显示的列表项, 保证选中项可见
+    fn top(&self, page_height: usize) -> usize {
+        (self.selected + 1).saturating_sub(page_height)
+    }
+
+    /// 全屏绘制列表, 第一行为标题栏
+    ///
+    /// 每项显示编号、`%` (当前缓冲区)、`+` (已修改)、`-` (只读)标记、文件名和行数
+    pub fn render(&self, frame: &mut Frame, size: Size, entries: &[BufferEntry], theme: &Theme) {
+        let Size { height, width } = size;
+        let page_height = height.saturating_sub(1);
+        let style = theme.style("status_bar");
+        let title = format!(
+            " Buffers ({})    <Enter> open  <d> close buffer  <q> back",
+            entries.len()
+        );
+        frame.print_styled(Position { x: 0, y: 0 }, &" ".repeat(width), style);
+        frame.print_styled(Position { x: 0, y: 0 }, &title, style);
+
+        let top = self.top(page_height);
+        for (offset, entry) in entries.iter().enumerate().skip(top).take(page_height) {
+            let row = offset - top + 1;
+            let flags = format!(
+                "{}{}{}",
+                if entry.active { '%' } else { ' ' },
+                if entry.modified { '+' } else { ' ' },
+                if entry.read_only { '-' } else { ' ' },
+            );
+            let text = format!(
+                "{:>3} {flags} {}  ({} lines)",
+                offset + 1,
+                entry.name,
+                entry.lines
+            );
+            let line_style = if offset == self.selected {
+                theme.style("selection")
+            } else {
+                theme.style("text")
+            };
+            frame.print_styled(Position { x: 0, y: row }, &" ".repeat(width), line_style);
+            frame.print_styled(Position { x: 0, y: row }, &text, line_style);
+        }
+    }
+}
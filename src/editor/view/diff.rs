@@ -0,0 +1,298 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 22:05:13
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 22:05:13
+ * @FilePath: \rim\src\editor\view\diff.rs
+ * @Description: 行级差异
+ */
+//! 差异模块
+//!
+//! 逐行比较两份文本, 生成类似 `diff -u` 的输出
+//!
+//! 先去掉相同的开头和结尾, 再对中间部分求最长公共子序列;
+//! 中间部分过大时不再求解, 整段显示为删除后插入
+
+use std::iter;
+
+/// 每个变化前后保留的上下文行数
+const CONTEXT: usize = 3;
+
+/// 最长公共子序列表的最大单元数, 超过时整段视为替换
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+/// 单行的比较结果
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Op {
+    /// 两边相同
+    Equal,
+    /// 只在旧文本中
+    Delete,
+    /// 只在新文本中
+    Insert,
+}
+
+/// 生成统一格式的差异
+///
+/// # 参数
+/// - `old` / `new`: 旧文本和新文本的行
+/// - `old_label` / `new_label`: `---` 和 `+++` 行显示的名称
+///
+/// # 返回
+/// 输出的每一行; 两边相同时只有 `---` 和 `+++` 两行
+pub fn unified(old: &[&str], new: &[&str], old_label: &str, new_label: &str) -> Vec<String> {
+    let ops = diff_ops(old, new);
+    // 每个操作开始时两边的行下标
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_index, mut new_index) = (0, 0);
+    for op in &ops {
+        positions.push((old_index, new_index));
+        match op {
+            Op::Equal => {
+                old_index += 1;
+                new_index += 1;
+            }
+            Op::Delete => old_index += 1,
+            Op::Insert => new_index += 1,
+        }
+    }
+    let is_change = |op: &Op| *op != Op::Equal;
+    let mut output = vec![format!("--- {old_label}"), format!("+++ {new_label}")];
+    let mut index = 0;
+    while let Some(first) = ops[index..].iter().position(is_change) {
+        let first = first + index;
+        let start = first.saturating_sub(CONTEXT).max(index);
+        // 相邻变化之间的相同行不超过两倍上下文时合并为一段
+        let mut end = first;
+        loop {
+            end += ops[end..].iter().take_while(|op| is_change(op)).count();
+            match ops[end..].iter().position(is_change) {
+                Some(gap) if gap <= 2 * CONTEXT => end += gap,
+                _ => break,
+            }
+        }
+        let stop = (end + CONTEXT).min(ops.len());
+        let hunk = &ops[start..stop];
+        let (old_start, new_start) = positions[start];
+        let old_count = hunk.iter().filter(|op| **op != Op::Insert).count();
+        let new_count = hunk.iter().filter(|op| **op != Op::Delete).count();
+        output.push(format!(
+            "@@ -{} +{} @@",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for (op, (old_index, new_index)) in hunk.iter().zip(&positions[start..stop]) {
+            output.push(match op {
+                Op::Equal => format!(" {}", old[*old_index]),
+                Op::Delete => format!("-{}", old[*old_index]),
+                Op::Insert => format!("+{}", new[*new_index]),
+            });
+        }
+        index = stop;
+    }
+    output
+}
+
+/// `@@` 行中的范围, 行号从 1 开始; 空范围写成前一行的行号
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/// 逐行比较, 去掉相同的开头和结尾后比较中间部分
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let mut ops = vec![Op::Equal; prefix];
+    ops.extend(middle_ops(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    ));
+    ops.extend(iter::repeat_n(Op::Equal, suffix));
+    ops
+}
+
+/// 用最长公共子序列比较两段文本
+fn middle_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (rows, columns) = (old.len(), new.len());
+    if (rows + 1).saturating_mul(columns + 1) > MAX_TABLE_CELLS {
+        let mut ops = vec![Op::Delete; rows];
+        ops.extend(iter::repeat_n(Op::Insert, columns));
+        return ops;
+    }
+    // `table[i * width + j]` 为 `old[i..]` 与 `new[j..]` 的最长公共子序列长度
+    let width = columns + 1;
+    let mut table = vec![0_u32; (rows + 1) * width];
+    for i in (0..rows).rev() {
+        for j in (0..columns).rev() {
+            table[i * width + j] = if old[i] == new[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+    let mut ops = Vec::with_capacity(rows + columns);
+    let (mut i, mut j) = (0, 0);
+    while i < rows && j < columns {
+        if old[i] == new[j] {
+            ops.push(Op::Equal);
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            ops.push(Op::Delete);
+            i += 1;
+        } else {
+            ops.push(Op::Insert);
+            j += 1;
+        }
+    }
+    ops.extend(iter::repeat_n(Op::Delete, rows - i));
+    ops.extend(iter::repeat_n(Op::Insert, columns - j));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `1` 到 `count` 的行
+    fn numbers(count: usize) -> Vec<String> {
+        (1..=count).map(|line| line.to_string()).collect()
+    }
+
+    /// 替换指定行(从 1 开始)后的行
+    fn replaced(lines: &[String], changes: &[(usize, &str)]) -> Vec<String> {
+        let mut lines = lines.to_vec();
+        for (line, text) in changes {
+            lines[line - 1] = (*text).to_string();
+        }
+        lines
+    }
+
+    fn diff(old: &[String], new: &[String]) -> Vec<String> {
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        unified(&old, &new, "a", "b")
+    }
+
+    /// 去掉 `---` / `+++` 两行
+    fn hunks(output: &[String]) -> Vec<&str> {
+        output[2..].iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn headers_only_when_equal() {
+        assert_eq!(unified(&[], &[], "a", "b"), ["--- a", "+++ b"]);
+        let lines = numbers(5);
+        assert_eq!(diff(&lines, &lines), ["--- a", "+++ b"]);
+    }
+
+    #[test]
+    fn empty_sides() {
+        let lines = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(hunks(&diff(&[], &lines)), ["@@ -0,0 +1,2 @@", "+a", "+b"]);
+        assert_eq!(hunks(&diff(&lines, &[])), ["@@ -1,2 +0,0 @@", "-a", "-b"]);
+    }
+
+    #[test]
+    fn single_change_keeps_context() {
+        let old = numbers(10);
+        let new = replaced(&old, &[(5, "five")]);
+        assert_eq!(
+            hunks(&diff(&old, &new)),
+            [
+                "@@ -2,7 +2,7 @@",
+                " 2",
+                " 3",
+                " 4",
+                "-5",
+                "+five",
+                " 6",
+                " 7",
+                " 8"
+            ]
+        );
+    }
+
+    #[test]
+    fn insertion_between_lines() {
+        let old = numbers(3);
+        let new = vec!["1".into(), "2".into(), "new".into(), "3".into()];
+        assert_eq!(
+            hunks(&diff(&old, &new)),
+            ["@@ -1,3 +1,4 @@", " 1", " 2", "+new", " 3"]
+        );
+    }
+
+    #[test]
+    fn changes_within_twice_the_context_share_a_hunk() {
+        let old = numbers(20);
+        let new = replaced(&old, &[(5, "five"), (12, "twelve")]);
+        let output = diff(&old, &new);
+        let output = hunks(&output);
+        assert_eq!(output[0], "@@ -2,14 +2,14 @@");
+        assert_eq!(
+            output.iter().filter(|line| line.starts_with("@@")).count(),
+            1
+        );
+        assert_eq!(output.len(), 1 + 14 + 2);
+    }
+
+    #[test]
+    fn changes_further_apart_get_separate_hunks() {
+        let old = numbers(20);
+        let new = replaced(&old, &[(5, "five"), (13, "thirteen")]);
+        let output = diff(&old, &new);
+        let headers: Vec<&str> = hunks(&output)
+            .into_iter()
+            .filter(|line| line.starts_with("@@"))
+            .collect();
+        assert_eq!(headers, ["@@ -2,7 +2,7 @@", "@@ -10,7 +10,7 @@"]);
+    }
+
+    #[test]
+    fn large_input_falls_back_to_replacement() {
+        let old = numbers(2001);
+        let mut new = old.clone();
+        new[0] = "first".into();
+        new[2000] = "last".into();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        // 中间部分为 2001 x 2001 行, 表格超过 `MAX_TABLE_CELLS`, 相同的行也不再匹配
+        let ops = diff_ops(&old, &new);
+        assert_eq!(ops[..2001], [Op::Delete; 2001]);
+        assert_eq!(ops[2001..], [Op::Insert; 2001]);
+        let output = unified(&old, &new, "a", "b");
+        assert_eq!(output[2], "@@ -1,2001 +1,2001 @@");
+    }
+
+    #[test]
+    fn small_input_finds_common_lines() {
+        let old = ["a", "x", "b", "y", "c"];
+        let new = ["a", "b", "z", "c"];
+        assert_eq!(
+            diff_ops(&old, &new),
+            [
+                Op::Equal,
+                Op::Delete,
+                Op::Equal,
+                Op::Delete,
+                Op::Insert,
+                Op::Equal
+            ]
+        );
+    }
+}
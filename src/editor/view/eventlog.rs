@@ -0,0 +1,125 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 17:42:36
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 17:42:36
+ * @FilePath: \rim\src\editor\view\eventlog.rs
+ * @Description: 带时间戳的事件日志历史
+ */
+//! 事件日志模块
+//!
+//! 保存所有事件的历史(超过容量时丢弃最旧的记录), 供信息面板和日志查看器使用,
+//! 并可以同时写入日志文件
+//!
+//! 时间戳使用 UTC, 日志文件中每行的格式为 `2026-10-18T09:42:36Z [INFO] message`
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 历史记录的最大条数
+const LOG_HISTORY_CAPACITY: usize = 10_000;
+
+/// 日志查看器可按其过滤的事件标签
+pub const LOG_TAGS: [&str; 7] = ["INFO", "MOVE", "EDIT", "NSUP", "KEY", "OTH", "HELP"];
+
+/// 一条事件记录
+pub struct LogEntry {
+    /// 记录时间
+    pub time: SystemTime,
+    /// 事件分类标签
+    pub tag: String,
+    /// 事件详细信息
+    pub info: String,
+}
+
+impl LogEntry {
+    /// 一天内的时间 `HH:MM:SS`
+    pub fn clock(&self) -> String {
+        let (_, (hour, minute, second)) = split_time(self.time);
+        format!("{hour:02}:{minute:02}:{second:02}")
+    }
+
+    /// 完整时间戳 `YYYY-MM-DDTHH:MM:SSZ`
+    fn timestamp(&self) -> String {
+        let (days, (hour, minute, second)) = split_time(self.time);
+        let (year, month, day) = civil_from_days(days);
+        format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+    }
+}
+
+/// 事件日志
+#[derive(Default)]
+pub struct EventLog {
+    /// 历史记录, 最旧的在前
+    entries: VecDeque<LogEntry>,
+    /// 同步写入的日志文件
+    file: Option<File>,
+}
+
+impl EventLog {
+    /// 设置同步写入的日志文件, 已有的历史会先写入文件
+    pub fn set_file(&mut self, mut file: File) {
+        for entry in &self.entries {
+            Self::write_entry(&mut file, entry);
+        }
+        self.file = Some(file);
+    }
+
+    /// 记录一条事件
+    pub fn push(&mut self, tag: &str, info: &str) {
+        let entry = LogEntry {
+            time: SystemTime::now(),
+            tag: tag.to_string(),
+            info: info.to_string(),
+        };
+        if let Some(file) = self.file.as_mut() {
+            Self::write_entry(file, &entry);
+        }
+        if self.entries.len() >= LOG_HISTORY_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// 所有历史记录, 最旧的在前
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    fn write_entry(file: &mut File, entry: &LogEntry) {
+        // 日志文件写入失败不影响编辑器
+        let _ = writeln!(file, "{} [{}] {}", entry.timestamp(), entry.tag, entry.info);
+    }
+}
+
+/// 把时间拆分为 UNIX 纪元以来的天数和一天内的时分秒
+fn split_time(time: SystemTime) -> (u64, (u64, u64, u64)) {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    #[allow(clippy::integer_division)]
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    #[allow(clippy::integer_division)]
+    let clock = (rest / 3600, rest % 3600 / 60, rest % 60);
+    (days, clock)
+}
+
+/// 把 UNIX 纪元以来的天数转换为公历日期
+///
+/// 算法来自 Howard Hinnant 的 `civil_from_days`
+#[allow(clippy::integer_division)]
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,391 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 23:40:18
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 23:40:18
+ * @FilePath: \rim\src\editor\view\explorer.rs
+ * @Description: 目录浏览
+ */
+//! 目录浏览模块
+//!
+//! - [`Explorer`] - 目录中的条目, 显示为只读缓冲区, 每个条目一行
+//! - [`ExplorerPrompt`] - 新建、重命名和删除条目时在状态栏输入或确认
+//!
+//! 条目按目录在前、名称升序排列, 第一行为上级目录 `../`(根目录除外);
+//! 目录名后加 `/`, 符号链接后加 `@`, 文件右侧显示大小
+
+use std::cmp::Reverse;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind};
+use std::path::{self, Path, PathBuf, MAIN_SEPARATOR};
+use unicode_width::UnicodeWidthStr;
+
+/// 名称列的最大宽度, 更长的名称把大小挤到右侧
+const MAX_NAME_WIDTH: usize = 48;
+
+/// 条目类型
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    /// 上级目录 `..`
+    Parent,
+    Directory,
+    File,
+    Symlink,
+    /// 设备、管道、套接字等
+    Other,
+}
+
+/// 目录中的一个条目
+pub struct Entry {
+    /// 文件名
+    pub name: String,
+    pub kind: EntryKind,
+    /// 文件大小, 目录和上级目录为 `None`
+    pub size: Option<u64>,
+}
+
+impl Entry {
+    /// 带类型标记的名称
+    fn label(&self) -> String {
+        match self.kind {
+            EntryKind::Parent => "../".to_string(),
+            EntryKind::Directory => format!("{}/", self.name),
+            EntryKind::Symlink => format!("{}@", self.name),
+            EntryKind::File | EntryKind::Other => self.name.clone(),
+        }
+    }
+}
+
+/// 一个目录的内容
+pub struct Explorer {
+    /// 目录的绝对路径
+    pub dir: PathBuf,
+    /// 条目, 与缓冲区的行一一对应
+    entries: Vec<Entry>,
+}
+
+impl Explorer {
+    /// 读取目录
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果目录不存在或无法读取; 单个条目无法读取时跳过
+    pub fn read(dir: &Path) -> Result<Self, Error> {
+        let dir = fs::canonicalize(dir)?;
+        let mut entries: Vec<Entry> = fs::read_dir(&dir)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_type = entry.file_type().ok()?;
+                let kind = if file_type.is_dir() {
+                    EntryKind::Directory
+                } else if file_type.is_symlink() {
+                    EntryKind::Symlink
+                } else if file_type.is_file() {
+                    EntryKind::File
+                } else {
+                    EntryKind::Other
+                };
+                let size = (kind == EntryKind::File)
+                    .then(|| entry.metadata().ok().map(|meta| meta.len()))
+                    .flatten();
+                Some(Entry {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    kind,
+                    size,
+                })
+            })
+            .collect();
+        entries.sort_by_cached_key(|entry| {
+            (
+                Reverse(entry.kind == EntryKind::Directory),
+                entry.name.to_lowercase(),
+                entry.name.clone(),
+            )
+        });
+        if dir.parent().is_some() {
+            entries.insert(
+                0,
+                Entry {
+                    name: "..".to_string(),
+                    kind: EntryKind::Parent,
+                    size: None,
+                },
+            );
+        }
+        Ok(Self { dir, entries })
+    }
+
+    /// 缓冲区标题: 目录路径, 以分隔符结尾
+    pub fn title(&self) -> String {
+        let dir = self.dir.to_string_lossy();
+        if dir.ends_with(MAIN_SEPARATOR) {
+            dir.into_owned()
+        } else {
+            format!("{dir}{MAIN_SEPARATOR}")
+        }
+    }
+
+    /// 缓冲区的内容, 每个条目一行
+    pub fn lines(&self) -> Vec<String> {
+        let labels: Vec<String> = self.entries.iter().map(Entry::label).collect();
+        let name_width = labels
+            .iter()
+            .map(|label| label.width())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_NAME_WIDTH);
+        self.entries
+            .iter()
+            .zip(labels)
+            .map(|(entry, label)| match entry.size {
+                Some(size) => {
+                    let padding = name_width.saturating_sub(label.width());
+                    format!("{label}{}  {:>6}", " ".repeat(padding), format_size(size))
+                }
+                None => label,
+            })
+            .collect()
+    }
+
+    /// 指定行的条目
+    pub fn entry(&self, line_index: usize) -> Option<&Entry> {
+        self.entries.get(line_index)
+    }
+
+    /// 名称对应的行
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.kind != EntryKind::Parent && entry.name == name)
+    }
+}
+
+/// 检查用户输入的条目名称, 确保操作不会离开所在目录
+///
+/// 名称不能为空、`.` 或 `..`, 不能是绝对路径, 也不能包含路径分隔符
+///
+/// # 错误
+/// 返回 `ErrorKind::InvalidInput` 如果名称不符合上述要求
+fn check_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "empty name"));
+    }
+    if name == "." || name == ".." {
+        return Err(Error::new(ErrorKind::InvalidInput, "invalid name"));
+    }
+    if name.contains(path::is_separator) || Path::new(name).has_root() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "name must not contain a path separator",
+        ));
+    }
+    Ok(())
+}
+
+/// 在目录中新建文件, 名称以 `/` 结尾时新建目录
+///
+/// # 错误
+/// 返回 `std::io::Error` 如果名称为空、包含路径分隔符、已存在或无法创建
+pub fn create(dir: &Path, name: &str) -> Result<(), Error> {
+    let (name, is_dir) = match name.strip_suffix('/') {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    check_name(name)?;
+    let path = dir.join(name);
+    if is_dir {
+        fs::create_dir(path)
+    } else {
+        File::create_new(path).map(|_| ())
+    }
+}
+
+/// 重命名目录中的条目, 不会覆盖已存在的条目
+///
+/// # 错误
+/// 返回 `std::io::Error` 如果名称为空、包含路径分隔符、新名称已存在或无法重命名
+pub fn rename(dir: &Path, from: &str, to: &str) -> Result<(), Error> {
+    check_name(from)?;
+    check_name(to)?;
+    let target = dir.join(to);
+    if target.symlink_metadata().is_ok() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            "target already exists",
+        ));
+    }
+    fs::rename(dir.join(from), target)
+}
+
+/// 删除目录中的条目, 目录只有为空时才能删除
+///
+/// # 错误
+/// 返回 `std::io::Error` 如果名称包含路径分隔符、条目不存在、目录不为空或无法删除
+pub fn delete(dir: &Path, name: &str) -> Result<(), Error> {
+    check_name(name)?;
+    let path = dir.join(name);
+    if path.symlink_metadata()?.is_dir() {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// 打开文件时使用的路径: 位于当前目录下时为相对路径, 否则为绝对路径
+pub fn display_path(path: &Path) -> String {
+    env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 文件大小, 如 `512`、`1.5K`、`23M`
+#[allow(clippy::integer_division)]
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let size = u128::from(size);
+    let mut scale = 1024_u128;
+    let mut unit = 0;
+    while size >= scale * 1024 && unit + 1 < UNITS.len() {
+        scale *= 1024;
+        unit += 1;
+    }
+    let tenths = size * 10 / scale;
+    if tenths < 100 {
+        format!("{}.{}{}", tenths / 10, tenths % 10, UNITS[unit])
+    } else {
+        format!("{}{}", size / scale, UNITS[unit])
+    }
+}
+
+/// 等待输入或确认的目录操作
+pub enum ExplorerPrompt {
+    /// 新建文件, 以 `/` 结尾时新建目录; 值为已输入的名称
+    Create(String),
+    /// 重命名条目
+    Rename {
+        /// 原名称
+        from: String,
+        /// 已输入的新名称
+        input: String,
+    },
+    /// 确认删除条目, 值为名称
+    Delete(String),
+}
+
+impl ExplorerPrompt {
+    /// 状态栏显示的文本, 光标位于末尾
+    pub fn text(&self) -> String {
+        match self {
+            Self::Create(input) => format!("New file (end with / for a directory): {input}"),
+            Self::Rename { from, input } => format!("Rename {from} to: {input}"),
+            Self::Delete(name) => format!("Delete {name}? [y/N] "),
+        }
+    }
+
+    /// 正在输入的名称, 确认删除时为 `None`
+    pub fn input_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::Create(input) | Self::Rename { input, .. } => Some(input),
+            Self::Delete(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试各自的临时目录, 每次运行前清空
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rim-explorer-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create scratch dir");
+        dir
+    }
+
+    fn is_invalid_input(result: Result<(), Error>) -> bool {
+        result.is_err_and(|err| err.kind() == ErrorKind::InvalidInput)
+    }
+
+    #[test]
+    fn create_file_and_directory() {
+        let dir = scratch_dir("create");
+        create(&dir, "a.txt").expect("create file");
+        create(&dir, "sub/").expect("create directory");
+        assert!(dir.join("a.txt").is_file());
+        assert!(dir.join("sub").is_dir());
+        let err = create(&dir, "a.txt").expect_err("file exists");
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rename_does_not_overwrite_existing_entry() {
+        let dir = scratch_dir("rename");
+        fs::write(dir.join("a"), "a").expect("write a");
+        fs::write(dir.join("b"), "b").expect("write b");
+        let err = rename(&dir, "a", "b").expect_err("target exists");
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(dir.join("b")).expect("read b"), "b");
+        rename(&dir, "a", "c").expect("rename");
+        assert!(!dir.join("a").exists());
+        assert_eq!(fs::read_to_string(dir.join("c")).expect("read c"), "a");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn delete_refuses_non_empty_directory() {
+        let dir = scratch_dir("delete");
+        fs::create_dir(dir.join("full")).expect("create full");
+        fs::write(dir.join("full").join("f"), "").expect("write f");
+        fs::create_dir(dir.join("empty")).expect("create empty");
+        fs::write(dir.join("file"), "").expect("write file");
+        assert!(delete(&dir, "full").is_err());
+        assert!(dir.join("full").join("f").exists());
+        delete(&dir, "empty").expect("delete empty directory");
+        delete(&dir, "file").expect("delete file");
+        assert!(!dir.join("empty").exists());
+        assert!(!dir.join("file").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn names_outside_the_directory_are_rejected() {
+        let dir = scratch_dir("reject");
+        let inner = dir.join("inner");
+        fs::create_dir(&inner).expect("create inner");
+        fs::write(dir.join("outside"), "").expect("write outside");
+        let absolute = dir.join("absolute");
+        let absolute = absolute.to_string_lossy();
+        for name in [
+            "",
+            ".",
+            "..",
+            "../x",
+            "sub/../../x",
+            "a/b",
+            absolute.as_ref(),
+        ] {
+            assert!(is_invalid_input(create(&inner, name)), "create {name:?}");
+            assert!(
+                is_invalid_input(rename(&inner, "f", name)),
+                "rename {name:?}"
+            );
+            assert!(is_invalid_input(delete(&inner, name)), "delete {name:?}");
+        }
+        assert!(is_invalid_input(create(&inner, "a//")));
+        assert!(is_invalid_input(create(&inner, "../x/")));
+        assert!(is_invalid_input(rename(&inner, "../outside", "moved")));
+        assert!(is_invalid_input(delete(&inner, "../outside")));
+        assert!(dir.join("outside").exists());
+        assert!(!dir.join("absolute").exists());
+        assert_eq!(fs::read_dir(&inner).expect("read inner").count(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
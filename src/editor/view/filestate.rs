@@ -0,0 +1,242 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 23:12:37
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 23:12:37
+ * @FilePath: \rim\src\editor\view\filestate.rs
+ * @Description: 跨会话保存每个文件的视图状态
+ */
+//! 文件视图状态模块
+//!
+//! 关闭缓冲区或退出编辑器时按绝对路径记录光标、滚动位置和命名标记, 再次打开同一文件时恢复
+//!
+//! 状态文件位于 `$XDG_STATE_HOME/rim/viewstate` (或 `~/.local/state/rim/viewstate`,
+//! Windows 下为 `%LOCALAPPDATA%\rim\viewstate`), 每行一个文件, 越靠后越新:
+//!
+//! ```text
+//! # rim view state
+//! 120 4 100 0 a=3,0;b=57,12<TAB>/home/user/notes.txt
+//! ```
+//!
+//! 依次为光标的行和列、顶部行、左侧列、标记, 制表符之后为绝对路径
+//!
+//! 没有标记时标记字段为 `-`; 保存时合并其他会话写入的记录, 删除已不存在的文件, 最多保留
+//! [`MAX_ENTRIES`] 条
+//!
+//! 以 `/` 开头的行留给搜索历史(每行一条, 越靠后越新)。搜索历史的记录推迟到实现搜索功能时:
+//! 目前没有任何代码写入搜索历史, 保存时只是原样保留这些行
+
+use super::location::Location;
+use super::GraphemeLocation;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// 状态文件的第一行
+const HEADER: &str = "# rim view state";
+
+/// 搜索历史行的前缀
+const SEARCH_PREFIX: char = '/';
+
+/// 最多记录的文件数, 超过时丢弃最早的记录
+const MAX_ENTRIES: usize = 1000;
+
+/// 一条记录: 文件的绝对路径和视图状态
+type Entry = (PathBuf, FileState);
+
+/// 单个文件的视图状态
+#[derive(Clone, Default)]
+pub struct FileState {
+    /// 光标位置
+    pub grapheme_location: GraphemeLocation,
+    /// 滚动偏移
+    pub scroll_offset: Location,
+    /// 命名标记
+    pub marks: Vec<(char, GraphemeLocation)>,
+}
+
+/// 所有文件的视图状态
+#[derive(Default)]
+pub struct FileStates {
+    /// 状态文件路径, 找不到状态目录时为 `None`, 此时不保存
+    path: Option<PathBuf>,
+    /// 启动时读取的记录
+    loaded: Vec<Entry>,
+    /// 本次会话更新的记录, 越靠后越新
+    recorded: Vec<Entry>,
+}
+
+impl FileStates {
+    /// 读取状态文件, 文件不存在时为空
+    ///
+    /// # 参数
+    /// - `dir`: 指定的状态目录, `None` 时使用默认位置
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果状态文件存在但无法读取
+    pub fn load(dir: Option<&Path>) -> Result<Self, Error> {
+        let dir = dir.map(Path::to_path_buf).or_else(state_dir);
+        let Some(path) = dir.map(|dir| dir.join("viewstate")) else {
+            return Ok(Self::default());
+        };
+        let loaded = match read_file(&path) {
+            Ok((entries, _)) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self {
+            path: Some(path),
+            loaded,
+            recorded: Vec::new(),
+        })
+    }
+
+    /// 文件上次的视图状态
+    pub fn get(&self, file_name: &str) -> Option<&FileState> {
+        let path = fs::canonicalize(file_name).ok()?;
+        self.recorded
+            .iter()
+            .chain(&self.loaded)
+            .find(|(entry, _)| *entry == path)
+            .map(|(_, state)| state)
+    }
+
+    /// 记录文件当前的视图状态
+    pub fn record(&mut self, file_name: &str, state: FileState) {
+        let Ok(path) = fs::canonicalize(file_name) else {
+            return;
+        };
+        // 路径中的换行无法写入按行分隔的状态文件
+        if path.to_string_lossy().contains('\n') {
+            return;
+        }
+        self.recorded.retain(|(entry, _)| *entry != path);
+        self.recorded.push((path, state));
+    }
+
+    /// 写入状态文件
+    ///
+    /// 重新读取状态文件, 保留其他会话写入的记录, 用本次会话的记录覆盖同一文件的旧记录;
+    /// 搜索历史行原样保留, 本次会话不会新增搜索历史
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果目录无法创建或文件无法写入
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if self.recorded.is_empty() {
+            return Ok(());
+        }
+        let (mut entries, searches) = read_file(path).unwrap_or_default();
+        entries.retain(|(entry, _)| self.recorded.iter().all(|(recorded, _)| recorded != entry));
+        entries.extend(self.recorded.iter().cloned());
+        entries.retain(|(entry, _)| entry.exists());
+        let skip = entries.len().saturating_sub(MAX_ENTRIES);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        let mut file = File::create(&temp)?;
+        writeln!(file, "{HEADER}")?;
+        for search in &searches {
+            writeln!(file, "{search}")?;
+        }
+        for (entry, state) in &entries[skip..] {
+            writeln!(file, "{}\t{}", format_state(state), entry.to_string_lossy())?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    }
+}
+
+/// 读取状态文件中的所有记录和搜索历史行, 跳过无法解析的行
+fn read_file(path: &Path) -> Result<(Vec<Entry>, Vec<String>), Error> {
+    let contents = fs::read_to_string(path)?;
+    let searches = contents
+        .lines()
+        .filter(|line| line.starts_with(SEARCH_PREFIX))
+        .map(String::from)
+        .collect();
+    let entries = contents
+        .lines()
+        .filter(|line| !line.starts_with(['#', SEARCH_PREFIX]))
+        .filter_map(|line| {
+            let (state, path) = line.split_once('\t')?;
+            Some((PathBuf::from(path), parse_state(state)?))
+        })
+        .collect();
+    Ok((entries, searches))
+}
+
+/// 格式化一条记录的状态部分
+fn format_state(state: &FileState) -> String {
+    let marks = if state.marks.is_empty() {
+        "-".to_string()
+    } else {
+        state
+            .marks
+            .iter()
+            .map(|(name, location)| {
+                format!("{name}={},{}", location.line_index, location.grapheme_index)
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    };
+    format!(
+        "{} {} {} {} {marks}",
+        state.grapheme_location.line_index,
+        state.grapheme_location.grapheme_index,
+        state.scroll_offset.y,
+        state.scroll_offset.x,
+    )
+}
+
+/// 解析一条记录的状态部分
+fn parse_state(text: &str) -> Option<FileState> {
+    let mut fields = text.split(' ');
+    let mut number = || fields.next()?.parse::<usize>().ok();
+    let (line_index, grapheme_index, top, left) = (number()?, number()?, number()?, number()?);
+    let marks = match fields.next()? {
+        "-" => Vec::new(),
+        marks => marks
+            .split(';')
+            .map(parse_mark)
+            .collect::<Option<Vec<_>>>()?,
+    };
+    Some(FileState {
+        grapheme_location: GraphemeLocation {
+            grapheme_index,
+            line_index,
+        },
+        scroll_offset: Location { x: left, y: top },
+        marks,
+    })
+}
+
+/// 解析 `a=行,列` 形式的标记
+fn parse_mark(text: &str) -> Option<(char, GraphemeLocation)> {
+    let (name, location) = text.split_once('=')?;
+    let mut chars = name.chars();
+    let name = chars.next().filter(|_| chars.next().is_none())?;
+    let (line_index, grapheme_index) = location.split_once(',')?;
+    Some((
+        name,
+        GraphemeLocation {
+            grapheme_index: grapheme_index.parse().ok()?,
+            line_index: line_index.parse().ok()?,
+        },
+    ))
+}
+
+/// 默认的状态目录
+fn state_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("rim"));
+    }
+    if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty()) {
+        return Some(PathBuf::from(home).join(".local").join("state").join("rim"));
+    }
+    env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("rim"))
+}
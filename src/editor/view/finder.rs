@@ -0,0 +1,615 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-19 00:26:15
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-19 00:26:15
+ * @FilePath: \rim\src\editor\view\finder.rs
+ * @Description: 模糊查找文件
+ */
+//! 文件查找模块
+//!
+//! 全屏列出工作目录下的文件, 输入的查询按模糊匹配打分排序, 右侧预览选中的文件
+//!
+//! - 后台线程按层遍历工作目录, 跳过 `.git` 和 `.gitignore` 忽略的路径, 分批交给主循环;
+//!   只列出普通文件和指向普通文件的符号链接, 不进入指向目录的符号链接, 因此不会陷入循环
+//! - 查询变长时只在已匹配的文件中重新打分, 新到达的文件按当前查询打分后加入
+//! - 查询中没有大写字母时不区分大小写
+//! - 选中项停留 [`PREVIEW_DELAY`] 后才读取预览, 按住方向键快速经过的文件不会被读取
+
+use super::buffer::Buffer;
+use super::gitignore::{self, Gitignore};
+use crate::editor::frame::Frame;
+use crate::editor::terminal::{Position, Size};
+use crate::editor::theme::Theme;
+use std::collections::VecDeque;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// 最多列出的文件数
+const MAX_FILES: usize = 100_000;
+
+/// 后台线程每批发送的文件数
+const BATCH_SIZE: usize = 256;
+
+/// 超过该大小的文件不预览
+const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+
+/// 列表和预览上方的标题栏和查询行
+const HEADER_ROWS: usize = 2;
+
+/// 终端宽度达到该值时才显示预览
+const MIN_PREVIEW_WIDTH: usize = 60;
+
+/// 选中项停留该时长后才读取预览
+const PREVIEW_DELAY: Duration = Duration::from_millis(100);
+
+/// 匹配到的文件
+struct Match {
+    /// 在 `candidates` 中的下标
+    index: usize,
+    score: usize,
+    /// 匹配的字符下标, 用于高亮
+    positions: Vec<usize>,
+}
+
+/// 选中文件的预览
+struct Preview {
+    /// 在 `candidates` 中的下标
+    index: usize,
+    /// 文件内容, 无法预览时为原因
+    contents: Result<Buffer, String>,
+}
+
+/// 文件查找器状态
+pub struct Finder {
+    /// 接收后台线程找到的文件, 遍历结束后为 `None`
+    receiver: Option<Receiver<Vec<String>>>,
+    /// 所有文件, 相对于工作目录, 用 `/` 分隔
+    candidates: Vec<String>,
+    /// 查询
+    query: String,
+    /// 匹配的文件, 按分数从高到低排列
+    matches: Vec<Match>,
+    /// 选中项在 `matches` 中的下标
+    selected: usize,
+    preview: Option<Preview>,
+    /// 读取选中项预览的时间, 预览已是最新或没有选中项时为 `None`
+    preview_due: Option<Instant>,
+}
+
+impl Finder {
+    /// 打开查找器, 在后台遍历目录
+    pub fn open(root: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || walk(&root, &sender));
+        Self {
+            receiver: Some(receiver),
+            candidates: Vec::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            preview: None,
+            preview_due: None,
+        }
+    }
+
+    /// 是否仍在遍历目录
+    pub fn is_loading(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// 取出后台线程已找到的文件, 按当前查询打分后加入列表, 不阻塞
+    pub fn receive(&mut self) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+        let start = self.candidates.len();
+        loop {
+            match receiver.try_recv() {
+                Ok(batch) => self.candidates.extend(batch),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    break;
+                }
+            }
+        }
+        if self.candidates.len() == start {
+            return;
+        }
+        let query: Vec<char> = self.query.chars().collect();
+        let new = (start..self.candidates.len())
+            .filter_map(|index| score(&query, &self.candidates[index], index));
+        self.matches.extend(new);
+        self.sort_matches();
+    }
+
+    /// 在查询末尾追加字符, 只在已匹配的文件中重新打分
+    pub fn insert(&mut self, ch: char) {
+        self.query.push(ch);
+        let query: Vec<char> = self.query.chars().collect();
+        let matches = mem::take(&mut self.matches);
+        self.matches = matches
+            .into_iter()
+            .filter_map(|old| score(&query, &self.candidates[old.index], old.index))
+            .collect();
+        self.selected = 0;
+        self.sort_matches();
+    }
+
+    /// 删除查询的最后一个字符, 重新为所有文件打分
+    pub fn backspace(&mut self) {
+        if self.query.pop().is_none() {
+            return;
+        }
+        let query: Vec<char> = self.query.chars().collect();
+        self.matches = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| score(&query, candidate, index))
+            .collect();
+        self.selected = 0;
+        self.sort_matches();
+    }
+
+    /// 上下移动选中项, 不会越过列表首尾
+    pub fn move_selection(&mut self, down: bool) {
+        self.selected = if down {
+            (self.selected + 1).min(self.matches.len().saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+        self.update_preview();
+    }
+
+    /// 选中的文件
+    pub fn selected_path(&self) -> Option<&str> {
+        let selected = self.matches.get(self.selected)?;
+        Some(&self.candidates[selected.index])
+    }
+
+    /// 按分数从高到低排序, 同分时短路径在前
+    fn sort_matches(&mut self) {
+        let candidates = &self.candidates;
+        self.matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| candidates[a.index].len().cmp(&candidates[b.index].len()))
+                .then_with(|| a.index.cmp(&b.index))
+        });
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+        self.update_preview();
+    }
+
+    /// 选中项变化后清空预览, [`PREVIEW_DELAY`] 之后由 [`Finder::load_due_preview`] 读取
+    fn update_preview(&mut self) {
+        let Some(selected) = self.matches.get(self.selected) else {
+            self.preview = None;
+            self.preview_due = None;
+            return;
+        };
+        if self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| preview.index == selected.index)
+        {
+            self.preview_due = None;
+            return;
+        }
+        self.preview = None;
+        self.preview_due = Some(Instant::now() + PREVIEW_DELAY);
+    }
+
+    /// 是否有尚未读取的预览
+    pub fn is_preview_pending(&self) -> bool {
+        self.preview_due.is_some()
+    }
+
+    /// 选中项已停留 [`PREVIEW_DELAY`] 时读取它的预览
+    pub fn load_due_preview(&mut self) {
+        if self.preview_due.is_none_or(|due| Instant::now() < due) {
+            return;
+        }
+        self.preview_due = None;
+        let Some(selected) = self.matches.get(self.selected) else {
+            return;
+        };
+        let path = &self.candidates[selected.index];
+        let contents = match fs::metadata(path) {
+            Ok(meta) if meta.len() > MAX_PREVIEW_BYTES => Err("(file too large to preview)".into()),
+            Ok(_) => Buffer::load_file(path).map_err(|_| "(cannot preview this file)".into()),
+            Err(err) => Err(format!("({err})")),
+        };
+        self.preview = Some(Preview {
+            index: selected.index,
+            contents,
+        });
+    }
+
+    /// 预览区域, 终端太窄时为 `None`
+    ///
+    /// # 返回
+    /// 左上角和尺寸
+    pub fn preview_area(size: Size) -> Option<(Position, Size)> {
+        let list_width = Self::list_width(size);
+        let height = size.height.saturating_sub(HEADER_ROWS);
+        (list_width < size.width && height > 0).then(|| {
+            (
+                Position {
+                    x: list_width + 1,
+                    y: HEADER_ROWS,
+                },
+                Size {
+                    height,
+                    width: size.width - list_width - 1,
+                },
+            )
+        })
+    }
+
+    /// 选中文件的内容, 没有选中项或无法预览时为 `None`
+    pub fn preview(&self) -> Option<&Buffer> {
+        self.preview.as_ref()?.contents.as_ref().ok()
+    }
+
+    /// 列表宽度, 终端较宽时占五分之二, 其余显示预览
+    #[allow(clippy::integer_division)]
+    fn list_width(size: Size) -> usize {
+        if size.width >= MIN_PREVIEW_WIDTH {
+            size.width * 2 / 5
+        } else {
+            size.width
+        }
+    }
+
+    /// 第一行显示的列表项, 保证选中项可见
+    fn top(&self, page_height: usize) -> usize {
+        (self.selected + 1).saturating_sub(page_height)
+    }
+
+    /// 光标位置: 查询行的末尾
+    pub fn cursor_position(&self, size: Size) -> Position {
+        Position {
+            x: (2 + self.query.width()).min(size.width.saturating_sub(1)),
+            y: 1,
+        }
+    }
+
+    /// 全屏绘制标题栏、查询行、列表和预览的分隔线; 可以预览时文件内容由调用者绘制
+    pub fn render(&self, frame: &mut Frame, size: Size, theme: &Theme) {
+        let Size { height, width } = size;
+        let style = theme.style("status_bar");
+        let loading = if self.is_loading() { " ..." } else { "" };
+        let title = format!(
+            " Files ({}/{}{loading})    <Enter> open  <Up>/<Down> select  <Esc> back",
+            self.matches.len(),
+            self.candidates.len()
+        );
+        frame.print_styled(Position { x: 0, y: 0 }, &" ".repeat(width), style);
+        frame.print_styled(Position { x: 0, y: 0 }, &title, style);
+        let query_row = Position { x: 0, y: 1 };
+        frame.print_styled(query_row, &" ".repeat(width), theme.style("text"));
+        frame.print_styled(query_row, &format!("> {}", self.query), theme.style("text"));
+
+        let list_width = Self::list_width(size);
+        let page_height = height.saturating_sub(HEADER_ROWS);
+        let top = self.top(page_height);
+        for row in HEADER_ROWS..height {
+            let position = Position { x: 0, y: row };
+            let offset = top + row - HEADER_ROWS;
+            let Some(matched) = self.matches.get(offset) else {
+                frame.print_styled(position, &" ".repeat(list_width), theme.style("text"));
+                continue;
+            };
+            let line_style = if offset == self.selected {
+                theme.style("selection")
+            } else {
+                theme.style("text")
+            };
+            frame.print_styled(position, &" ".repeat(list_width), line_style);
+            let mut col = 0;
+            let mut positions = matched.positions.iter().peekable();
+            for (index, ch) in self.candidates[matched.index].chars().enumerate() {
+                let ch_width = ch.width().unwrap_or(0);
+                if col + ch_width > list_width {
+                    break;
+                }
+                let at = Position { x: col, y: row };
+                if positions.next_if(|position| **position == index).is_some() {
+                    frame.print_styled(at, &ch.to_string(), theme.style("search_match"));
+                } else {
+                    frame.print_styled(at, &ch.to_string(), line_style);
+                }
+                col += ch_width;
+            }
+        }
+
+        let Some((at, area)) = Self::preview_area(size) else {
+            return;
+        };
+        for y in at.y..at.y + area.height {
+            frame.print_styled(Position { x: list_width, y }, "│", theme.style("gutter"));
+        }
+        if let Some(Preview {
+            contents: Err(reason),
+            ..
+        }) = &self.preview
+        {
+            frame.print_styled(at, reason, theme.style("empty_row"));
+        }
+    }
+}
+
+/// 模糊匹配并打分
+///
+/// 查询的字符必须按顺序出现在路径中; 连续匹配、匹配在单词开头(`/`、`_`、`-`、`.` 之后或驼峰)
+/// 和匹配在文件名中得分更高, 匹配之间的间隔扣分
+///
+/// # 参数
+/// - `query`: 查询的字符
+/// - `candidate`: 路径
+/// - `index`: 路径在列表中的下标
+///
+/// # 返回
+/// 不匹配时返回 `None`
+fn score(query: &[char], candidate: &str, index: usize) -> Option<Match> {
+    let case_sensitive = query.iter().any(|ch| ch.is_uppercase());
+    let fold = |ch: char| {
+        if case_sensitive {
+            ch
+        } else {
+            ch.to_lowercase().next().unwrap_or(ch)
+        }
+    };
+    let text: Vec<char> = candidate.chars().collect();
+    if query.is_empty() {
+        return Some(Match {
+            index,
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    // 先从前往后找到最早的完整匹配, 再从它的结尾往前找最紧凑的匹配
+    let mut query_index = 0;
+    let mut end = 0;
+    for (text_index, ch) in text.iter().enumerate() {
+        if fold(*ch) == query[query_index] {
+            query_index += 1;
+            if query_index == query.len() {
+                end = text_index;
+                break;
+            }
+        }
+    }
+    if query_index < query.len() {
+        return None;
+    }
+    let mut positions = Vec::with_capacity(query.len());
+    let mut query_index = query.len();
+    for text_index in (0..=end).rev() {
+        if fold(text[text_index]) == query[query_index - 1] {
+            positions.push(text_index);
+            query_index -= 1;
+            if query_index == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let name_start = text
+        .iter()
+        .rposition(|ch| *ch == '/')
+        .map_or(0, |index| index + 1);
+    let mut score = 0_usize;
+    let mut gaps = 0;
+    let mut previous: Option<usize> = None;
+    for &position in &positions {
+        score += 16;
+        let boundary = match position.checked_sub(1).map(|before| text[before]) {
+            None => true,
+            Some(before) => {
+                matches!(before, '/' | '_' | '-' | '.' | ' ')
+                    || (before.is_lowercase() && text[position].is_uppercase())
+            }
+        };
+        if boundary {
+            score += 10;
+        }
+        if position >= name_start {
+            score += 4;
+        }
+        match previous {
+            Some(previous) if position == previous + 1 => score += 12,
+            Some(previous) => gaps += position - previous - 1,
+            None => {}
+        }
+        previous = Some(position);
+    }
+    Some(Match {
+        index,
+        score: score.saturating_sub(gaps),
+        positions,
+    })
+}
+
+/// 按层遍历目录, 把找到的文件分批发送给主循环
+///
+/// 不进入指向目录的符号链接; 接收端已丢弃(查找器被关闭)或达到 [`MAX_FILES`] 时停止
+fn walk(root: &Path, sender: &Sender<Vec<String>>) {
+    let mut queue = VecDeque::from([(root.to_path_buf(), String::new(), Vec::new())]);
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut count = 0;
+    while let Some((dir, prefix, mut ignores)) = queue.pop_front() {
+        if let Ok(contents) = fs::read_to_string(dir.join(".gitignore")) {
+            ignores.push(Arc::new(Gitignore::parse(&prefix, &contents)));
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+        entries.sort_by_key(fs::DirEntry::file_name);
+        for entry in entries {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name == ".git" {
+                continue;
+            }
+            let path = format!("{prefix}{name}");
+            let is_dir = file_type.is_dir();
+            if gitignore::is_ignored(&ignores, &path, is_dir) {
+                continue;
+            }
+            if is_dir {
+                queue.push_back((entry.path(), format!("{path}/"), ignores.clone()));
+                continue;
+            }
+            // 符号链接按目标判断, 跳过指向目录、失效的链接以及管道、设备等无法作为文件打开的条目
+            let is_file = if file_type.is_symlink() {
+                fs::metadata(entry.path()).is_ok_and(|meta| meta.is_file())
+            } else {
+                file_type.is_file()
+            };
+            if !is_file {
+                continue;
+            }
+            batch.push(path);
+            count += 1;
+            if batch.len() == BATCH_SIZE && sender.send(mem::take(&mut batch)).is_err() {
+                return;
+            }
+            if count == MAX_FILES {
+                let _ = sender.send(batch);
+                return;
+            }
+        }
+    }
+    let _ = sender.send(batch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn score_of(query: &str, candidate: &str) -> Option<Match> {
+        let query: Vec<char> = query.chars().collect();
+        score(&query, candidate, 0)
+    }
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(score_of("abc", "a/b/c").is_some());
+        assert!(score_of("cba", "a/b/c").is_none());
+        assert!(score_of("", "anything").is_some());
+    }
+
+    #[test]
+    fn lowercase_query_ignores_case() {
+        assert!(score_of("readme", "README.md").is_some());
+        assert!(score_of("readme", "ReadMe.md").is_some());
+    }
+
+    #[test]
+    fn uppercase_query_is_case_sensitive() {
+        assert!(score_of("ReadMe", "ReadMe.md").is_some());
+        assert!(score_of("ReadMe", "readme.md").is_none());
+        assert!(score_of("README", "ReadMe.md").is_none());
+    }
+
+    #[test]
+    fn positions_use_the_tightest_window() {
+        let matched = score_of("ab", "a_xab").expect("match");
+        assert_eq!(matched.positions, [3, 4]);
+        let matched = score_of("abc", "a/b/c").expect("match");
+        assert_eq!(matched.positions, [0, 2, 4]);
+    }
+
+    #[test]
+    fn contiguous_match_outranks_scattered() {
+        let contiguous = score_of("main", "src/main.rs").expect("match");
+        let scattered = score_of("main", "mxaxixn.rs").expect("match");
+        assert!(contiguous.score > scattered.score);
+    }
+
+    #[test]
+    fn word_boundary_match_outranks_scattered() {
+        let boundary = score_of("fb", "foo_bar.rs").expect("match");
+        let scattered = score_of("fb", "xfxxxxbx.rs").expect("match");
+        assert!(boundary.score > scattered.score);
+        let camel = score_of("fb", "fooBar.rs").expect("match");
+        let plain = score_of("fb", "foobar.rs").expect("match");
+        assert!(camel.score > plain.score);
+    }
+
+    #[test]
+    fn file_name_match_outranks_directory_match() {
+        let name = score_of("view", "src/editor/view.rs").expect("match");
+        let dir = score_of("view", "view/src/editor.rs").expect("match");
+        assert!(name.score > dir.score);
+    }
+
+    /// 遍历目录, 返回找到的所有文件
+    fn walk_all(root: &Path) -> Vec<String> {
+        let (sender, receiver) = mpsc::channel();
+        walk(root, &sender);
+        drop(sender);
+        receiver.into_iter().flatten().collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_lists_file_symlinks_and_skips_directory_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = env::temp_dir().join(format!("rim-finder-{}-symlinks", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("dir")).expect("create dir");
+        fs::write(root.join("dir").join("file.txt"), "").expect("write file");
+        symlink(root.join("dir"), root.join("dir_link")).expect("link dir");
+        symlink(&root, root.join("dir").join("loop")).expect("link root");
+        symlink(root.join("dir").join("file.txt"), root.join("file_link")).expect("link file");
+        symlink(root.join("missing"), root.join("broken_link")).expect("link missing");
+        let files = walk_all(&root);
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(files, ["file_link", "dir/file.txt"]);
+    }
+
+    #[test]
+    fn preview_waits_until_selection_settles() {
+        let candidates = vec!["Cargo.toml".to_string(), "README.md".to_string()];
+        let mut finder = Finder {
+            receiver: None,
+            matches: (0..candidates.len())
+                .filter_map(|index| score(&[], &candidates[index], index))
+                .collect(),
+            candidates,
+            query: String::new(),
+            selected: 0,
+            preview: None,
+            preview_due: None,
+        };
+        finder.move_selection(true);
+        finder.load_due_preview();
+        assert!(finder.preview().is_none());
+        assert!(finder.is_preview_pending());
+        thread::sleep(PREVIEW_DELAY);
+        finder.load_due_preview();
+        assert!(!finder.is_preview_pending());
+        let preview = finder.preview().expect("preview loaded");
+        assert_eq!(preview.file_name.as_deref(), Some("README.md"));
+        // 选中另一个文件时重新等待
+        finder.move_selection(false);
+        assert!(finder.preview().is_none());
+        assert!(finder.is_preview_pending());
+    }
+}
@@ -0,0 +1,291 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-19 00:08:44
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-19 00:08:44
+ * @FilePath: \rim\src\editor\view\gitignore.rs
+ * @Description: .gitignore 规则
+ */
+//! `.gitignore` 规则模块
+//!
+//! 支持常用的规则写法:
+//! - 空行和 `#` 开头的行被忽略, `\#` / `\!` 转义开头的字符
+//! - `!` 开头的规则重新包含之前被忽略的路径
+//! - `/` 结尾的规则只匹配目录
+//! - 不含 `/` 的规则匹配任意层级的名称, 否则相对于 `.gitignore` 所在目录匹配
+//! - 通配符 `*`、`?`、`[a-z]`、`[!abc]` 不匹配 `/`, `**` 匹配任意层目录
+//!
+//! 子目录中的 `.gitignore` 优先于上级目录中的, 同一文件中后面的规则优先
+
+/// 单条规则
+struct Rule {
+    /// 去掉 `!`、开头和结尾的 `/` 之后的模式
+    pattern: Vec<char>,
+    /// 是否为 `!` 开头的重新包含规则
+    negated: bool,
+    /// 是否只匹配目录
+    dir_only: bool,
+    /// 是否相对于 `.gitignore` 所在目录匹配整个路径
+    anchored: bool,
+}
+
+/// 一个 `.gitignore` 文件中的规则
+pub struct Gitignore {
+    /// 文件所在目录相对于遍历起点的路径, 起点为空, 否则以 `/` 结尾
+    base: String,
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    /// 解析 `.gitignore` 文件内容
+    ///
+    /// # 参数
+    /// - `base`: 文件所在目录相对于遍历起点的路径, 起点为空, 否则以 `/` 结尾
+    /// - `contents`: 文件内容
+    pub fn parse(base: &str, contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.strip_prefix('/').unwrap_or(line);
+                (!line.is_empty()).then(|| Rule {
+                    pattern: line.chars().collect(),
+                    negated,
+                    dir_only,
+                    anchored,
+                })
+            })
+            .collect();
+        Self {
+            base: base.to_string(),
+            rules,
+        }
+    }
+
+    /// 用本文件的规则判断路径
+    ///
+    /// # 参数
+    /// - `path`: 相对于遍历起点的路径, 用 `/` 分隔
+    /// - `is_dir`: 路径是否为目录
+    ///
+    /// # 返回
+    /// 没有规则匹配时为 `None`, 否则为是否忽略
+    fn matched(&self, path: &str, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base)?;
+        let relative: Vec<char> = relative.chars().collect();
+        let name_start = relative
+            .iter()
+            .rposition(|ch| *ch == '/')
+            .map_or(0, |index| index + 1);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                let text = if rule.anchored {
+                    &relative[..]
+                } else {
+                    &relative[name_start..]
+                };
+                (is_dir || !rule.dir_only) && glob_match(&rule.pattern, text)
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// 路径是否被忽略
+///
+/// # 参数
+/// - `ignores`: 从遍历起点到路径所在目录的各层 `.gitignore`, 上级目录在前
+/// - `path`: 相对于遍历起点的路径, 用 `/` 分隔
+/// - `is_dir`: 路径是否为目录
+pub fn is_ignored(ignores: &[impl AsRef<Gitignore>], path: &str, is_dir: bool) -> bool {
+    ignores
+        .iter()
+        .rev()
+        .find_map(|ignore| ignore.as_ref().matched(path, is_dir))
+        .unwrap_or(false)
+}
+
+/// 通配符匹配
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` 也可以匹配零层目录
+            if let ['/', after @ ..] = rest {
+                if glob_match(after, text) {
+                    return true;
+                }
+            }
+            (0..=text.len()).any(|skip| glob_match(rest, &text[skip..]))
+        }
+        ['*', rest @ ..] => {
+            let segment = text.iter().position(|ch| *ch == '/').unwrap_or(text.len());
+            (0..=segment).any(|skip| glob_match(rest, &text[skip..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(text, [first, ..] if *first != '/') && glob_match(rest, &text[1..])
+        }
+        ['[', class @ ..] => match match_class(class, text.first().copied()) {
+            Some((matched, rest)) => matched && glob_match(rest, &text[1..]),
+            // 没有结束的 `]` 时按普通字符处理
+            None => text.first() == Some(&'[') && glob_match(class, &text[1..]),
+        },
+        ['\\', escaped, rest @ ..] | [escaped, rest @ ..] => {
+            text.first() == Some(escaped) && glob_match(rest, &text[1..])
+        }
+    }
+}
+
+/// 匹配 `[...]` 字符类
+///
+/// # 参数
+/// - `class`: `[` 之后的模式
+/// - `ch`: 待匹配的字符, 已到文本末尾时为 `None`
+///
+/// # 返回
+/// 是否匹配以及 `]` 之后的模式; 没有结束的 `]` 时返回 `None`
+fn match_class(class: &[char], ch: Option<char>) -> Option<(bool, &[char])> {
+    let (negated, mut rest) = match class {
+        ['!' | '^', rest @ ..] => (true, rest),
+        _ => (false, class),
+    };
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match rest {
+            [']', tail @ ..] if !first => {
+                let matched = ch.is_some_and(|ch| ch != '/' && matched != negated);
+                return Some((matched, tail));
+            }
+            [start, '-', end, tail @ ..] if *end != ']' => {
+                matched |= ch.is_some_and(|ch| (*start..=*end).contains(&ch));
+                rest = tail;
+            }
+            [single, tail @ ..] => {
+                matched |= ch == Some(*single);
+                rest = tail;
+            }
+            [] => return None,
+        }
+        first = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// 用遍历起点的一个 `.gitignore` 判断路径
+    fn ignored(rules: &str, path: &str, is_dir: bool) -> bool {
+        is_ignored(&[Arc::new(Gitignore::parse("", rules))], path, is_dir)
+    }
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        glob_match(&pattern, &text)
+    }
+
+    #[test]
+    fn double_star_matches_zero_or_more_directories() {
+        assert!(glob("a/**/b", "a/b"));
+        assert!(glob("a/**/b", "a/x/y/b"));
+        assert!(!glob("a/**/b", "ab"));
+        assert!(ignored("**/cache", "cache", true));
+        assert!(ignored("**/cache", "x/y/cache", true));
+        assert!(ignored("logs/**", "logs/a/b.txt", false));
+    }
+
+    #[test]
+    fn single_star_and_question_mark_stop_at_slash() {
+        assert!(glob("*.rs", "main.rs"));
+        assert!(!glob("*.rs", "src/main.rs"));
+        assert!(glob("?.rs", "a.rs"));
+        assert!(!glob("?", "/"));
+    }
+
+    #[test]
+    fn unanchored_pattern_matches_name_at_any_depth() {
+        assert!(ignored("build", "build", true));
+        assert!(ignored("build", "src/build", true));
+        assert!(ignored("*.log", "a/b/c.log", false));
+    }
+
+    #[test]
+    fn anchored_pattern_matches_from_base() {
+        assert!(ignored("/build", "build", true));
+        assert!(!ignored("/build", "src/build", true));
+        assert!(ignored("doc/*.txt", "doc/a.txt", false));
+        assert!(!ignored("doc/*.txt", "x/doc/a.txt", false));
+        assert!(!ignored("doc/*.txt", "doc/x/a.txt", false));
+    }
+
+    #[test]
+    fn trailing_slash_matches_directories_only() {
+        assert!(ignored("target/", "target", true));
+        assert!(!ignored("target/", "target", false));
+    }
+
+    #[test]
+    fn negation_reincludes_later_match() {
+        let rules = "*.log\n!keep.log\n";
+        assert!(ignored(rules, "a.log", false));
+        assert!(!ignored(rules, "keep.log", false));
+        // 后面的规则优先
+        assert!(ignored("!keep.log\n*.log\n", "keep.log", false));
+    }
+
+    #[test]
+    fn comments_blank_lines_and_escapes() {
+        let rules = "# comment\n\n\\#notes\n\\!bang\n";
+        assert!(!ignored(rules, "comment", false));
+        assert!(ignored(rules, "#notes", false));
+        assert!(ignored(rules, "!bang", false));
+        assert!(glob(r"\*", "*"));
+        assert!(!glob(r"\*", "a"));
+    }
+
+    #[test]
+    fn character_classes() {
+        assert!(glob("file[0-9].txt", "file1.txt"));
+        assert!(!glob("file[0-9].txt", "filea.txt"));
+        assert!(glob("file[!a-z].txt", "file1.txt"));
+        assert!(!glob("file[!a-z].txt", "filea.txt"));
+        assert!(glob("[^x]", "y"));
+        assert!(glob("[]]", "]"));
+        assert!(!glob("a[/]b", "a/b"));
+    }
+
+    #[test]
+    fn unterminated_class_is_literal() {
+        assert!(glob("a[b", "a[b"));
+        assert!(!glob("a[b", "ab"));
+        assert!(ignored("[", "[", false));
+    }
+
+    #[test]
+    fn nested_gitignore_applies_below_its_directory_and_wins() {
+        let ignores = [
+            Arc::new(Gitignore::parse("", "*.log\n")),
+            Arc::new(Gitignore::parse("sub/", "!keep.log\nlocal\n")),
+        ];
+        assert!(is_ignored(&ignores, "sub/a.log", false));
+        assert!(!is_ignored(&ignores, "sub/keep.log", false));
+        assert!(is_ignored(&ignores, "sub/local", false));
+        assert!(!is_ignored(&ignores, "local", false));
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-19 10:05:12
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-19 10:05:12
+ * @FilePath: \rim\src\editor\view\help.rs
+ * @Description: 按键帮助
+ */
+//! 按键帮助模块
+//!
+//! 按 `F1` 时在日志查看器中显示的帮助页, 每行为按键和说明; 完整说明见 README
+
+/// 主要的按键绑定, 按功能分组
+pub const KEY_BINDINGS: [(&str, &str); 32] = [
+    ("F1", "Show this help (Ctrl+h with keyboard enhancement)"),
+    ("Ctrl+q", "Quit"),
+    ("Ctrl+z", "Suspend to the shell, resume with fg"),
+    ("h j k l / arrows", "Move the cursor"),
+    ("PgUp / PgDn", "Move one page up / down"),
+    ("Home / End", "Move to the start / end of the line"),
+    (
+        "Ctrl+Home / Ctrl+End",
+        "Move to the start / end of the file",
+    ),
+    ("%", "Jump to the matching bracket"),
+    ("m{a-z} / '{a-z}", "Set a mark / jump to a mark"),
+    ("Ctrl+o / Ctrl+i", "Go back / forward in the jump list"),
+    ("Ctrl+e / Ctrl+y", "Scroll the view one line down / up"),
+    ("Ctrl+d / Ctrl+u", "Scroll the view half a page down / up"),
+    (
+        "zt / zz / zb",
+        "Put the cursor line at the top / middle / bottom",
+    ),
+    ("u", "Undo the last edit"),
+    ("F", "Follow a growing file, any move stops following"),
+    ("Ctrl+l", "Show or hide the info panel"),
+    ("Ctrl+g", "Open the event log, <Tab> filters by tag"),
+    ("Ctrl+b", "Open the buffer list"),
+    ("]b / [b", "Switch to the next / previous buffer"),
+    ("Ctrl+x", "Close the current buffer"),
+    ("Ctrl+p", "Find a file in the working directory"),
+    ("Ctrl+w s / v", "Split the window horizontally / vertically"),
+    ("Ctrl+w h j k l / w", "Focus another window"),
+    ("Ctrl+w + - > < =", "Resize windows, = makes them equal"),
+    ("Ctrl+w c / o", "Close this window / all other windows"),
+    ("Enter / -", "Directory: open the entry / go to the parent"),
+    ("n / r / d / R", "Directory: new, rename, delete, reload"),
+    ("Space / b", "Pager: next / previous page"),
+    ("d / u", "Pager: half a page down / up"),
+    ("g / G", "Pager: go to the start / end of the file"),
+    ("q", "Pager: quit"),
+    (
+        "q / Esc",
+        "Leave the buffer list, the event log or this help",
+    ),
+];
@@ -0,0 +1,151 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 17:05:12
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 17:05:12
+ * @FilePath: \rim\src\editor\view\infopanel.rs
+ * @Description: 可配置的信息(事件日志)面板
+ */
+//! 信息面板模块
+//!
+//! 面板显示 [`EventLog`] 中最近的事件, 高度、位置(顶部/底部/隐藏)和显示的标签都可以在运行时配置,
+//! 隐藏时事件仍会被记录, 重新显示后即可看到最近的事件
+
+use super::eventlog::EventLog;
+use crate::editor::frame::Frame;
+use crate::editor::terminal::Position;
+use crate::editor::theme::Theme;
+
+/// 默认面板高度
+pub const DEFAULT_INFO_HEIGHT: usize = 5;
+
+/// 信息面板位置
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InfoPosition {
+    /// 缓冲区上方
+    #[default]
+    Top,
+    /// 缓冲区下方, 状态栏上方
+    Bottom,
+    /// 不显示
+    Hidden,
+}
+
+impl InfoPosition {
+    /// 解析面板位置设置
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            "hidden" | "none" => Ok(Self::Hidden),
+            _ => Err(format!(
+                "invalid info position {value:?}, expected top / bottom / hidden"
+            )),
+        }
+    }
+}
+
+/// 信息面板
+pub struct InfoPanel {
+    /// 显示时占用的行数
+    height: usize,
+    /// 显示位置, 隐藏时为上一次的显示位置
+    position: InfoPosition,
+    /// 是否显示
+    visible: bool,
+    /// 面板中不显示的事件标签
+    muted_tags: Vec<String>,
+}
+
+impl Default for InfoPanel {
+    fn default() -> Self {
+        Self {
+            height: DEFAULT_INFO_HEIGHT,
+            position: InfoPosition::Top,
+            visible: true,
+            muted_tags: Vec::new(),
+        }
+    }
+}
+
+impl InfoPanel {
+    /// 应用配置
+    ///
+    /// # 参数
+    /// - `height`: 面板行数, 为 0 时等同于隐藏
+    /// - `position`: 面板位置
+    /// - `muted_tags`: 面板中不显示的事件标签(不区分大小写)
+    pub fn configure(&mut self, height: usize, position: InfoPosition, muted_tags: &[String]) {
+        self.height = height;
+        self.visible = position != InfoPosition::Hidden;
+        if position != InfoPosition::Hidden {
+            self.position = position;
+        }
+        self.muted_tags = muted_tags.to_vec();
+    }
+
+    fn is_muted(&self, tag: &str) -> bool {
+        self.muted_tags
+            .iter()
+            .any(|muted| muted.eq_ignore_ascii_case(tag))
+    }
+
+    /// 切换显示/隐藏
+    ///
+    /// # 返回
+    /// 切换后是否显示
+    pub fn toggle(&mut self) -> bool {
+        self.visible = !self.visible;
+        self.visible
+    }
+
+    /// 当前实际占用的行数, 隐藏时为 0
+    pub fn rows(&self) -> usize {
+        if self.visible {
+            self.height
+        } else {
+            0
+        }
+    }
+
+    /// 当前实际的显示位置
+    pub fn position(&self) -> InfoPosition {
+        if self.rows() == 0 {
+            InfoPosition::Hidden
+        } else {
+            self.position
+        }
+    }
+
+    /// 从指定行开始绘制最近的未屏蔽事件, 标签与内容使用不同样式
+    ///
+    /// # 参数
+    /// - `top`: 面板第一行
+    /// - `rows`: 布局分配给面板的行数
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        top: usize,
+        rows: usize,
+        log: &EventLog,
+        theme: &Theme,
+    ) {
+        let mut recent: Vec<_> = log
+            .entries()
+            .rev()
+            .filter(|entry| !self.is_muted(&entry.tag))
+            .take(rows)
+            .collect();
+        recent.reverse();
+        for (offset, entry) in recent.into_iter().enumerate() {
+            let row = top + offset;
+            let tag = format!("[{:<4}]", entry.tag);
+            let x = frame.print_styled(Position { x: 0, y: row }, &tag, theme.style("info.tag"));
+            frame.print_styled(
+                Position { x, y: row },
+                &format!(" {}", entry.info),
+                theme.style("info.text"),
+            );
+        }
+    }
+}
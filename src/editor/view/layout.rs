@@ -0,0 +1,148 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 19:12:40
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 19:12:40
+ * @FilePath: \rim\src\editor\view\layout.rs
+ * @Description: 根据终端尺寸划分界面区域
+ */
+//! 布局模块
+//!
+//! 根据终端尺寸把屏幕划分为信息面板、窗口区和状态栏, 再把每个窗口划分为行号栏、文本区和窗口状态行
+//!
+//! 空间不足时按优先级依次放弃可选区域, 保证文本区至少有一行一列:
+//! 1. 信息面板: 文本区少于 [`MIN_TEXT_HEIGHT`] 行时隐藏
+//! 2. 行号栏: 窗口文本区少于 [`MIN_TEXT_WIDTH`] 列时隐藏
+//! 3. 状态栏: 只剩一行时隐藏
+//!
+//! 有多个窗口时每个窗口底部都有自己的状态行, 不再显示全局状态栏, 它的行留给窗口区
+
+use super::InfoPosition;
+use crate::editor::terminal::Size;
+
+/// 状态栏高度
+pub const STATUS_BAR_SIZE: usize = 1;
+
+/// 显示信息面板时文本区至少保留的行数
+pub const MIN_TEXT_HEIGHT: usize = 3;
+
+/// 显示行号栏时文本区至少保留的列数
+pub const MIN_TEXT_WIDTH: usize = 8;
+
+/// 屏幕上的矩形区域
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// 界面各区域的位置和大小
+///
+/// 尺寸为 0 时所有区域都为空
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    /// 信息面板第一行
+    pub info_top: usize,
+    /// 信息面板行数, 不显示时为 0
+    pub info_rows: usize,
+    /// 窗口区, 由窗口布局树继续划分
+    pub windows: Rect,
+    /// 状态栏所在行, 不显示时为 `None`
+    pub status_row: Option<usize>,
+}
+
+impl Layout {
+    /// 计算布局
+    ///
+    /// # 参数
+    /// - `size`: 终端尺寸
+    /// - `info_rows`: 信息面板希望占用的行数
+    /// - `info_position`: 信息面板位置
+    /// - `status_bar`: 是否显示全局状态栏(只有一个窗口时)
+    pub fn compute(
+        size: Size,
+        info_rows: usize,
+        info_position: InfoPosition,
+        status_bar: bool,
+    ) -> Self {
+        let Size { height, width } = size;
+        let status_rows = if status_bar && height > STATUS_BAR_SIZE {
+            STATUS_BAR_SIZE
+        } else {
+            0
+        };
+        let info_rows = match info_position {
+            InfoPosition::Hidden => 0,
+            _ if height < info_rows + status_rows + MIN_TEXT_HEIGHT => 0,
+            InfoPosition::Top | InfoPosition::Bottom => info_rows,
+        };
+        let buffer_height = height - status_rows - info_rows;
+        let buffer_top = match info_position {
+            InfoPosition::Top => info_rows,
+            InfoPosition::Bottom | InfoPosition::Hidden => 0,
+        };
+        let info_top = match info_position {
+            InfoPosition::Top => 0,
+            InfoPosition::Bottom | InfoPosition::Hidden => buffer_height,
+        };
+        Self {
+            info_top,
+            info_rows,
+            windows: Rect {
+                x: 0,
+                y: buffer_top,
+                width,
+                height: buffer_height,
+            },
+            status_row: (status_rows > 0).then(|| height - status_rows),
+        }
+    }
+}
+
+/// 一个窗口内各区域的位置和大小
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pane {
+    /// 窗口第一列
+    pub left: usize,
+    /// 文本区第一行
+    pub top: usize,
+    /// 窗口宽度
+    pub width: usize,
+    /// 文本区行数
+    pub height: usize,
+    /// 行号栏宽度, 不显示时为 0
+    pub gutter_width: usize,
+    /// 文本区列数
+    pub text_width: usize,
+    /// 窗口状态行所在行, 不显示时为 `None`
+    pub status_row: Option<usize>,
+}
+
+impl Pane {
+    /// 计算窗口内的布局
+    ///
+    /// # 参数
+    /// - `rect`: 窗口区域
+    /// - `gutter_width`: 行号栏希望占用的列数
+    /// - `status_line`: 是否在窗口底部显示状态行(有多个窗口时), 窗口只有一行时不显示
+    pub fn compute(rect: Rect, gutter_width: usize, status_line: bool) -> Self {
+        let status_line = status_line && rect.height > 1;
+        let height = rect.height - usize::from(status_line);
+        let gutter_width = if rect.width >= gutter_width + MIN_TEXT_WIDTH {
+            gutter_width
+        } else {
+            0
+        };
+        Self {
+            left: rect.x,
+            top: rect.y,
+            width: rect.width,
+            height,
+            gutter_width,
+            text_width: rect.width - gutter_width,
+            status_row: status_line.then_some(rect.y + height),
+        }
+    }
+}
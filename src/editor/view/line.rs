@@ -182,6 +182,45 @@ impl Line {
         result
     }
 
+    /// 获取指定索引处的字素
+    pub fn grapheme(&self, grapheme_index: usize) -> Option<&str> {
+        self.fragments
+            .get(grapheme_index)
+            .map(|frag| frag.grapheme.as_str())
+    }
+
+    /// 按顺序遍历所有字素
+    pub fn graphemes(&self) -> impl Iterator<Item = &str> {
+        self.fragments.iter().map(|frag| frag.grapheme.as_str())
+    }
+
+    /// 按顺序遍历每个字素的起始列和显示宽度
+    pub fn column_spans(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.fragments.iter().scan(0, |col, frag| {
+            let width = match frag.rendered_width {
+                GraphemeWidth::Half => 1,
+                GraphemeWidth::Full => 2,
+            };
+            let start = *col;
+            *col += width;
+            Some((start, width))
+        })
+    }
+
+    /// 行的原始文本
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    /// 指定字素在原始文本中的字节下标, 超出范围时为文本长度
+    pub fn byte_index(&self, grapheme_index: usize) -> usize {
+        self.fragments
+            .iter()
+            .take(grapheme_index)
+            .map(|frag| frag.grapheme.len())
+            .sum()
+    }
+
     pub fn fragment_len(&self) -> usize {
         self.fragments.len()
     }
@@ -0,0 +1,177 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 17:58:04
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 17:58:04
+ * @FilePath: \rim\src\editor\view\logviewer.rs
+ * @Description: 全屏事件日志查看器
+ */
+//! 日志查看器模块
+//!
+//! 全屏显示 [`EventLog`] 的完整历史, 可以滚动并按标签过滤
+//!
+//! 打开时停留在最新的记录, 滚动到末尾后会继续跟随新记录
+//!
+//! 也用于显示 [`KEY_BINDINGS`] 帮助页, 此时从第一行开始显示且不能过滤
+
+use super::eventlog::{EventLog, LogEntry, LOG_TAGS};
+use super::help::KEY_BINDINGS;
+use crate::editor::editorcommand::Direction;
+use crate::editor::frame::Frame;
+use crate::editor::terminal::{Position, Size};
+use crate::editor::theme::Theme;
+
+/// 日志查看器状态
+#[derive(Default)]
+pub struct LogViewer {
+    /// 只显示该标签的记录, `None` 显示全部
+    filter: Option<&'static str>,
+    /// 第一行显示的记录在过滤结果中的下标, `None` 表示跟随最新记录
+    top: Option<usize>,
+    /// 显示按键帮助而不是事件日志
+    help: bool,
+}
+
+impl LogViewer {
+    /// 显示按键帮助的查看器
+    pub fn help() -> Self {
+        Self {
+            filter: None,
+            top: Some(0),
+            help: true,
+        }
+    }
+
+    /// 可显示的总行数
+    fn total(&self, log: &EventLog) -> usize {
+        if self.help {
+            KEY_BINDINGS.len()
+        } else {
+            self.filtered(log).len()
+        }
+    }
+
+    /// 过滤后的记录
+    fn filtered<'a>(&self, log: &'a EventLog) -> Vec<&'a LogEntry> {
+        log.entries()
+            .filter(|entry| self.filter.is_none_or(|tag| entry.tag == tag))
+            .collect()
+    }
+
+    /// 第一行显示的记录下标
+    fn top_index(&self, total: usize, page_height: usize) -> usize {
+        let last_top = total.saturating_sub(page_height);
+        self.top.map_or(last_top, |top| top.min(last_top))
+    }
+
+    /// 滚动日志
+    ///
+    /// # 参数
+    /// - `direction`: 滚动方式, 支持上下、翻页和首尾
+    /// - `log`: 事件日志
+    /// - `page_height`: 可显示的记录行数
+    pub fn scroll(&mut self, direction: Direction, log: &EventLog, page_height: usize) {
+        let total = self.total(log);
+        let last_top = total.saturating_sub(page_height);
+        let top = self.top_index(total, page_height);
+        let page = page_height.saturating_sub(1).max(1);
+        let top = match direction {
+            Direction::Up => top.saturating_sub(1),
+            Direction::Down => top.saturating_add(1),
+            Direction::PageUp => top.saturating_sub(page),
+            Direction::PageDown => top.saturating_add(page),
+            Direction::FileStart | Direction::Home => 0,
+            Direction::FileEnd | Direction::End => last_top,
+            Direction::Left | Direction::Right => top,
+        };
+        // 滚动到末尾后恢复跟随
+        self.top = (top < last_top).then_some(top);
+    }
+
+    /// 切换到下一个(或上一个)过滤标签, 顺序为全部、[`LOG_TAGS`] 中的各个标签
+    pub fn cycle_filter(&mut self, forward: bool) {
+        if self.help {
+            return;
+        }
+        let count = LOG_TAGS.len() + 1;
+        let current = self
+            .filter
+            .and_then(|tag| LOG_TAGS.iter().position(|known| *known == tag))
+            .map_or(0, |index| index + 1);
+        let next = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+        self.filter = next.checked_sub(1).map(|index| LOG_TAGS[index]);
+        self.top = None;
+    }
+
+    /// 全屏绘制日志或帮助, 第一行为标题栏
+    pub fn render(&self, frame: &mut Frame, size: Size, log: &EventLog, theme: &Theme) {
+        if self.help {
+            self.render_help(frame, size, theme);
+            return;
+        }
+        let Size { height, width } = size;
+        let page_height = height.saturating_sub(1);
+        let entries = self.filtered(log);
+        let top = self.top_index(entries.len(), page_height);
+
+        let style = theme.style("status_bar");
+        let filter = self.filter.unwrap_or("ALL");
+        let bottom = (top + page_height).min(entries.len());
+        let title = format!(
+            " Event log [{filter}] {bottom}/{}    <Tab> filter  <q> close",
+            entries.len()
+        );
+        frame.print_styled(Position { x: 0, y: 0 }, &" ".repeat(width), style);
+        frame.print_styled(Position { x: 0, y: 0 }, &title, style);
+
+        for (offset, entry) in entries.iter().skip(top).take(page_height).enumerate() {
+            let row = offset + 1;
+            let head = format!("{} [{:<4}]", entry.clock(), entry.tag);
+            let x = frame.print_styled(Position { x: 0, y: row }, &head, theme.style("info.tag"));
+            frame.print_styled(
+                Position { x, y: row },
+                &format!(" {}", entry.info),
+                theme.style("info.text"),
+            );
+        }
+    }
+
+    /// 全屏绘制按键帮助, 按键列对齐
+    fn render_help(&self, frame: &mut Frame, size: Size, theme: &Theme) {
+        let Size { height, width } = size;
+        let page_height = height.saturating_sub(1);
+        let total = KEY_BINDINGS.len();
+        let top = self.top_index(total, page_height);
+
+        let style = theme.style("status_bar");
+        let bottom = (top + page_height).min(total);
+        let title = format!(" Key bindings {bottom}/{total}    <j>/<k> scroll  <q> close");
+        frame.print_styled(Position { x: 0, y: 0 }, &" ".repeat(width), style);
+        frame.print_styled(Position { x: 0, y: 0 }, &title, style);
+
+        let key_width = KEY_BINDINGS
+            .iter()
+            .map(|(keys, _)| keys.len())
+            .max()
+            .unwrap_or(0);
+        for (offset, (keys, description)) in
+            KEY_BINDINGS.iter().skip(top).take(page_height).enumerate()
+        {
+            let row = offset + 1;
+            let x = frame.print_styled(
+                Position { x: 0, y: row },
+                &format!(" {keys:<key_width$}"),
+                theme.style("info.tag"),
+            );
+            frame.print_styled(
+                Position { x, y: row },
+                &format!("  {description}"),
+                theme.style("info.text"),
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 10:25:03
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 10:25:03
+ * @FilePath: \rim\src\editor\view\marks.rs
+ * @Description: 命名标记与跳转列表
+ */
+//! 标记与跳转列表模块
+//!
+//! - [`Marks`] - 命名标记(`ma` 设置, `'a` 跳转)
+//! - [`JumpList`] - 记录大幅度跳转, 支持 `Ctrl+o` / `Ctrl+i` 前后导航
+//!
+//! 两者都以 [`GraphemeLocation`] 存储位置, 文本插入/删除行时随之平移
+
+use super::GraphemeLocation;
+use std::collections::HashMap;
+
+/// 跳转列表最大长度
+const JUMP_LIST_CAPACITY: usize = 100;
+
+/// 按行的插入/删除平移一个位置
+///
+/// 位于被删除行内的位置会移动到删除区域的起始行
+pub fn shift_location(
+    location: &mut GraphemeLocation,
+    line_index: usize,
+    removed: usize,
+    inserted: usize,
+) {
+    if location.line_index < line_index {
+        return;
+    }
+    if location.line_index < line_index + removed {
+        location.line_index = line_index;
+        location.grapheme_index = 0;
+    } else {
+        location.line_index = location.line_index - removed + inserted;
+    }
+}
+
+/// 命名标记集合
+#[derive(Default)]
+pub struct Marks {
+    marks: HashMap<char, GraphemeLocation>,
+}
+
+impl Marks {
+    /// 设置标记
+    ///
+    /// # 参数
+    /// - `name`: 标记名, 仅支持 ASCII 字母
+    ///
+    /// # 返回
+    /// 标记名非法时返回 `false`
+    pub fn set(&mut self, name: char, location: GraphemeLocation) -> bool {
+        if !name.is_ascii_alphabetic() {
+            return false;
+        }
+        self.marks.insert(name, location);
+        true
+    }
+
+    /// 获取标记位置
+    pub fn get(&self, name: char) -> Option<GraphemeLocation> {
+        self.marks.get(&name).copied()
+    }
+
+    /// 所有标记, 按标记名排序
+    pub fn sorted(&self) -> Vec<(char, GraphemeLocation)> {
+        let mut marks: Vec<_> = self
+            .marks
+            .iter()
+            .map(|(name, location)| (*name, *location))
+            .collect();
+        marks.sort_unstable_by_key(|(name, _)| *name);
+        marks
+    }
+
+    /// 在 `line_index` 处删除 `removed` 行并插入 `inserted` 行后平移所有标记
+    pub fn shift_lines(&mut self, line_index: usize, removed: usize, inserted: usize) {
+        for location in self.marks.values_mut() {
+            shift_location(location, line_index, removed, inserted);
+        }
+    }
+}
+
+/// 跳转列表
+///
+/// `current == entries.len()` 表示当前不在历史中导航
+#[derive(Default)]
+pub struct JumpList {
+    entries: Vec<GraphemeLocation>,
+    current: usize,
+}
+
+impl JumpList {
+    /// 记录一次跳转的出发位置
+    ///
+    /// 会丢弃当前位置之后的前进历史
+    pub fn push(&mut self, from: GraphemeLocation) {
+        self.entries.truncate(self.current);
+        if self.entries.last() != Some(&from) {
+            self.entries.push(from);
+        }
+        if self.entries.len() > JUMP_LIST_CAPACITY {
+            self.entries.remove(0);
+        }
+        self.current = self.entries.len();
+    }
+
+    /// 后退到上一个跳转位置
+    ///
+    /// # 参数
+    /// - `current`: 当前光标位置, 首次后退时被记录, 以便再前进回来
+    pub fn back(&mut self, current: GraphemeLocation) -> Option<GraphemeLocation> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        if self.current == self.entries.len() {
+            if self.entries.last() != Some(&current) {
+                self.entries.push(current);
+            }
+            self.current = self.entries.len() - 1;
+        }
+        if self.current == 0 {
+            return None;
+        }
+        self.current -= 1;
+        self.entries.get(self.current).copied()
+    }
+
+    /// 前进到下一个跳转位置
+    pub fn forward(&mut self) -> Option<GraphemeLocation> {
+        if self.current + 1 >= self.entries.len() {
+            return None;
+        }
+        self.current += 1;
+        self.entries.get(self.current).copied()
+    }
+
+    /// 在 `line_index` 处删除 `removed` 行并插入 `inserted` 行后平移所有记录
+    pub fn shift_lines(&mut self, line_index: usize, removed: usize, inserted: usize) {
+        for location in &mut self.entries {
+            shift_location(location, line_index, removed, inserted);
+        }
+    }
+}
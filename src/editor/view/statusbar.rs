@@ -0,0 +1,134 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 16:40:26
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 16:40:26
+ * @FilePath: \rim\src\editor\view\statusbar.rs
+ * @Description: 底部状态栏
+ */
+//! 状态栏模块
+//!
+//! 在底部一行(或分割后每个窗口的底部)显示文件路径、修改/只读标记、换行符、编码、文件类型和光标位置
+//!
+//! 宽度不足时按优先级依次省略编码、换行符、文件类型, 再缩短文件路径
+
+use super::buffer::Buffer;
+use super::GraphemeLocation;
+use crate::editor::frame::Frame;
+use crate::editor::style::Style;
+use crate::editor::terminal::Position;
+use unicode_width::UnicodeWidthStr;
+
+/// 文件路径至少保留的显示宽度, 不足时才开始省略右侧信息
+const MIN_PATH_WIDTH: usize = 12;
+
+/// 省略号
+const ELLIPSIS: &str = "…";
+
+/// 绘制状态栏
+///
+/// # 参数
+/// - `frame`: 目标帧
+/// - `at`: 状态栏左端位置
+/// - `width`: 状态栏宽度
+/// - `buffer`: 当前缓冲区
+/// - `location`: 光标的字素位置
+/// - `style`: 状态栏样式
+pub fn render(
+    frame: &mut Frame,
+    at: Position,
+    width: usize,
+    buffer: &Buffer,
+    location: GraphemeLocation,
+    style: Style,
+) {
+    frame.print_styled(at, &" ".repeat(width), style);
+
+    let mut left_flags = String::new();
+    if buffer.modified {
+        left_flags.push_str(" [+]");
+    }
+    if buffer.read_only {
+        left_flags.push_str(" [RO]");
+    }
+    if buffer.is_streaming() {
+        left_flags.push_str(" [reading]");
+    }
+    let path = buffer.name();
+
+    let total = buffer.lines.len();
+    let percent = ((location.line_index + 1) * 100)
+        .checked_div(total)
+        .unwrap_or(100);
+    let position = format!(
+        "{}:{} / {total} ({percent}%)",
+        location.line_index + 1,
+        location.grapheme_index + 1
+    );
+    let file_type = buffer.syntax.map_or("text", |syntax| syntax.name);
+    // 宽度不足时从末尾开始省略
+    let mut optional = vec![
+        file_type.to_string(),
+        buffer.line_ending.to_string(),
+        buffer.encoding().to_string(),
+    ];
+
+    let left_min = MIN_PATH_WIDTH.min(path.width()) + left_flags.width() + 2;
+    let right = loop {
+        let mut items = optional.clone();
+        items.push(position.clone());
+        let right = format!("{} ", items.join(" | "));
+        if optional.is_empty() || left_min + right.width() <= width {
+            break right;
+        }
+        optional.pop();
+    };
+
+    let path_width = width
+        .saturating_sub(right.width())
+        .saturating_sub(left_flags.width() + 2);
+    let left = format!(" {}{left_flags} ", shorten_path(path, path_width));
+    let x = frame.print_styled(at, &left, style);
+    let right_x = (at.x + width).saturating_sub(right.width()).max(x);
+    frame.print_styled(
+        Position {
+            x: right_x,
+            y: at.y,
+        },
+        &right,
+        style,
+    );
+}
+
+/// 把路径缩短到指定显示宽度
+///
+/// 优先省略开头的目录(`…/dir/file`), 仍然过长时从左侧截断文件名
+fn shorten_path(path: &str, max_width: usize) -> String {
+    if path.width() <= max_width {
+        return path.to_string();
+    }
+    let separators: Vec<usize> = path
+        .match_indices(['/', '\\'])
+        .map(|(index, _)| index)
+        .collect();
+    for index in separators {
+        let shortened = format!("{ELLIPSIS}{}", &path[index..]);
+        if shortened.width() <= max_width {
+            return shortened;
+        }
+    }
+    let mut shortened = String::new();
+    let mut shortened_width = ELLIPSIS.width();
+    for ch in path.chars().rev() {
+        let ch_width = ch.to_string().width();
+        if shortened_width + ch_width > max_width {
+            break;
+        }
+        shortened.insert(0, ch);
+        shortened_width += ch_width;
+    }
+    if max_width >= ELLIPSIS.width() {
+        shortened.insert_str(0, ELLIPSIS);
+    }
+    shortened
+}
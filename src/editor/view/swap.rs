@@ -0,0 +1,186 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 22:41:06
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 22:41:06
+ * @FilePath: \rim\src\editor\view\swap.rs
+ * @Description: 交换文件与崩溃恢复
+ */
+//! 交换文件模块
+//!
+//! 已修改的缓冲区定期把内容写入缓存目录中的交换文件, 编辑器崩溃后再次打开同一文件时可以恢复
+//!
+//! 交换文件位于 `$XDG_CACHE_HOME/rim/swap` (或 `~/.cache/rim/swap`, Windows 下为
+//! `%LOCALAPPDATA%\rim\swap`), 文件名是把绝对路径中的分隔符替换为 `%` 后加上 `.swp`:
+//!
+//! ```text
+//! rim swap
+//! pid 12345
+//! path /home/user/notes.txt
+//!
+//! 缓冲区内容...
+//! ```
+//!
+//! 缓冲区恢复为未修改、被关闭或编辑器正常退出时删除交换文件
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// 交换文件的第一行
+const MAGIC: &str = "rim swap";
+
+/// 缓冲区的交换文件
+pub struct SwapFile {
+    /// 交换文件路径
+    pub path: PathBuf,
+    /// 上次写入时缓冲区的编辑计数, 还没有写入时为 `None`
+    pub written: Option<u64>,
+    /// 打开文件时发现的旧交换文件还在等待选择恢复、查看差异或删除
+    pub stale: bool,
+}
+
+impl SwapFile {
+    /// 创建尚未写入的交换文件
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            written: None,
+            stale: false,
+        }
+    }
+
+    /// 写入缓冲区内容
+    ///
+    /// 先写入临时文件再重命名, 崩溃时不会留下写了一半的交换文件
+    ///
+    /// # 参数
+    /// - `file_name`: 缓冲区对应的文件
+    /// - `lines`: 缓冲区内容
+    /// - `edits`: 缓冲区当前的编辑计数
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果目录无法创建或文件无法写入
+    pub fn write<'a>(
+        &mut self,
+        file_name: &str,
+        lines: impl Iterator<Item = &'a str>,
+        edits: u64,
+    ) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = self.path.with_extension("swp.tmp");
+        let mut file = File::create(&temp)?;
+        write!(file, "{MAGIC}\npid {}\npath {file_name}\n\n", process::id())?;
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp, &self.path)?;
+        self.written = Some(edits);
+        Ok(())
+    }
+
+    /// 删除已写入的交换文件
+    pub fn remove(&mut self) {
+        if self.written.take().is_some() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// 从交换文件中读取的内容
+pub struct SwapContents {
+    /// 写入交换文件的进程
+    pub pid: u32,
+    /// 缓冲区内容
+    pub lines: Vec<String>,
+}
+
+impl SwapContents {
+    /// 读取交换文件
+    ///
+    /// # 错误
+    /// 返回 `std::io::Error` 如果文件无法读取, 格式不正确时为 `ErrorKind::InvalidData`
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read(path)?;
+        let contents = String::from_utf8_lossy(&contents);
+        let invalid = || Error::new(ErrorKind::InvalidData, "not a rim swap file");
+        let (header, body) = contents.split_once("\n\n").ok_or_else(invalid)?;
+        let mut header = header.lines();
+        if header.next() != Some(MAGIC) {
+            return Err(invalid());
+        }
+        let pid = header
+            .next()
+            .and_then(|line| line.strip_prefix("pid "))
+            .and_then(|pid| pid.parse().ok())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            pid,
+            lines: body.lines().map(String::from).collect(),
+        })
+    }
+
+    /// 写入交换文件的进程是否仍在编辑该文件(包括当前进程的另一个缓冲区)
+    pub fn in_use(&self) -> bool {
+        self.pid == process::id() || process_alive(self.pid)
+    }
+}
+
+/// 文件对应的交换文件路径
+///
+/// # 参数
+/// - `file_name`: 文件路径
+/// - `dir`: 指定的缓存目录, `None` 时使用默认位置
+///
+/// # 返回
+/// 文件不存在或找不到缓存目录时返回 `None`
+pub fn swap_path(file_name: &str, dir: Option<&Path>) -> Option<PathBuf> {
+    let dir = dir.map(Path::to_path_buf).or_else(cache_dir)?;
+    let absolute = fs::canonicalize(file_name).ok()?;
+    let encoded: String = absolute
+        .to_string_lossy()
+        .chars()
+        .map(|ch| {
+            if matches!(ch, '/' | '\\' | ':') {
+                '%'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    Some(dir.join("swap").join(format!("{encoded}.swp")))
+}
+
+/// 默认的缓存目录
+fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("rim"));
+    }
+    if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty()) {
+        return Some(PathBuf::from(home).join(".cache").join("rim"));
+    }
+    env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("rim"))
+}
+
+/// 进程是否仍在运行
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: 信号 0 只检查进程是否存在, 不会真正发送信号
+    let result = unsafe { libc::kill(pid, 0) };
+    // 没有权限向该进程发送信号时它也是存在的
+    result == 0 || Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// 进程是否仍在运行, 无法检查的平台上视为已退出
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    false
+}
@@ -0,0 +1,284 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 09:12:40
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 09:12:40
+ * @FilePath: \rim\src\editor\view\syntax.rs
+ * @Description: 语法定义, 区分代码/字符串/注释
+ */
+//! 语法定义模块
+//!
+//! 根据文件扩展名选择语法定义, 并将每行字素划分为
+//! 代码、关键字、数字、字符串字面量或注释,
+//! 供语法高亮使用, 也供括号匹配等功能跳过非代码区域
+
+use super::line::Line;
+use std::path::Path;
+
+/// 字素所属的词法类别
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Code,
+    Keyword,
+    Number,
+    String,
+    Comment,
+}
+
+/// 跨行扫描状态
+///
+/// 块注释和字符串可能跨越多行, 扫描下一行时需要延续上一行的状态
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ScanState {
+    #[default]
+    Code,
+    BlockComment,
+    String(char),
+}
+
+/// 一种文件类型的语法定义
+pub struct Syntax {
+    /// 语法名称
+    pub name: &'static str,
+    /// 匹配的文件扩展名
+    extensions: &'static [&'static str],
+    /// 单行注释起始符
+    line_comment: Option<&'static str>,
+    /// 块注释起止符
+    block_comment: Option<(&'static str, &'static str)>,
+    /// 字符串引号
+    string_quotes: &'static [char],
+    /// 是否识别 `'x'` 形式的字符字面量(避免与 Rust 生命周期混淆)
+    char_literals: bool,
+    /// 关键字
+    keywords: &'static [&'static str],
+}
+
+static SYNTAXES: &[Syntax] = &[
+    Syntax {
+        name: "Rust",
+        extensions: &["rs"],
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        string_quotes: &['"'],
+        char_literals: true,
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+            "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+            "trait", "true", "type", "unsafe", "use", "where", "while",
+        ],
+    },
+    Syntax {
+        name: "JSON",
+        extensions: &["json"],
+        line_comment: None,
+        block_comment: None,
+        string_quotes: &['"'],
+        char_literals: false,
+        keywords: &["true", "false", "null"],
+    },
+    Syntax {
+        name: "C",
+        extensions: &["c", "h", "cpp", "hpp", "cc", "java", "js", "ts", "go"],
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        string_quotes: &['"', '\'', '`'],
+        char_literals: false,
+        keywords: &[
+            "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+            "enum", "export", "extends", "false", "for", "func", "function", "if", "import", "let",
+            "new", "null", "package", "private", "public", "return", "static", "struct", "switch",
+            "this", "throw", "true", "try", "typedef", "var", "void", "while",
+        ],
+    },
+    Syntax {
+        name: "Python",
+        extensions: &["py"],
+        line_comment: Some("#"),
+        block_comment: None,
+        string_quotes: &['"', '\''],
+        char_literals: false,
+        keywords: &[
+            "and", "as", "class", "def", "elif", "else", "except", "False", "finally", "for",
+            "from", "if", "import", "in", "is", "lambda", "None", "not", "or", "pass", "raise",
+            "return", "True", "try", "while", "with", "yield",
+        ],
+    },
+    Syntax {
+        name: "TOML",
+        extensions: &["toml"],
+        line_comment: Some("#"),
+        block_comment: None,
+        string_quotes: &['"', '\''],
+        char_literals: false,
+        keywords: &["true", "false"],
+    },
+];
+
+impl Syntax {
+    /// 根据文件名的扩展名查找语法定义
+    ///
+    /// # 返回
+    /// 未识别的文件类型返回 `None`
+    pub fn detect(filename: &str) -> Option<&'static Self> {
+        let extension = Path::new(filename).extension()?.to_str()?;
+        SYNTAXES.iter().find(|syntax| {
+            syntax
+                .extensions
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// 将一行的每个字素划分为词法类别
+    ///
+    /// # 参数
+    /// - `line`: 待扫描的行
+    /// - `state`: 行首状态, 扫描结束后更新为行尾状态
+    pub fn classify(&self, line: &Line, state: &mut ScanState) -> Vec<TokenKind> {
+        let graphemes: Vec<&str> = line.graphemes().collect();
+        let mut kinds = Vec::with_capacity(graphemes.len());
+        let mut index = 0;
+        while index < graphemes.len() {
+            match *state {
+                ScanState::BlockComment => {
+                    let end = self.block_comment.map_or("", |(_, end)| end);
+                    if matches_at(&graphemes, index, end) {
+                        let len = end.chars().count();
+                        kinds.extend(std::iter::repeat_n(TokenKind::Comment, len));
+                        index += len;
+                        *state = ScanState::Code;
+                    } else {
+                        kinds.push(TokenKind::Comment);
+                        index += 1;
+                    }
+                }
+                ScanState::String(quote) => {
+                    kinds.push(TokenKind::String);
+                    if graphemes[index] == "\\" && index + 1 < graphemes.len() {
+                        kinds.push(TokenKind::String);
+                        index += 1;
+                    } else if is_char(graphemes[index], quote) {
+                        *state = ScanState::Code;
+                    }
+                    index += 1;
+                }
+                ScanState::Code => {
+                    if self
+                        .line_comment
+                        .is_some_and(|start| matches_at(&graphemes, index, start))
+                    {
+                        kinds.extend(std::iter::repeat_n(
+                            TokenKind::Comment,
+                            graphemes.len() - index,
+                        ));
+                        break;
+                    }
+                    if let Some((start, _)) = self.block_comment {
+                        if matches_at(&graphemes, index, start) {
+                            let len = start.chars().count();
+                            kinds.extend(std::iter::repeat_n(TokenKind::Comment, len));
+                            index += len;
+                            *state = ScanState::BlockComment;
+                            continue;
+                        }
+                    }
+                    if self.char_literals {
+                        if let Some(len) = char_literal_len(&graphemes, index) {
+                            kinds.extend(std::iter::repeat_n(TokenKind::String, len));
+                            index += len;
+                            continue;
+                        }
+                    }
+                    if let Some((len, kind)) = self.word_at(&graphemes, index) {
+                        kinds.extend(std::iter::repeat_n(kind, len));
+                        index += len;
+                        continue;
+                    }
+                    match self
+                        .string_quotes
+                        .iter()
+                        .find(|quote| is_char(graphemes[index], **quote))
+                    {
+                        Some(quote) => {
+                            kinds.push(TokenKind::String);
+                            *state = ScanState::String(*quote);
+                        }
+                        None => kinds.push(TokenKind::Code),
+                    }
+                    index += 1;
+                }
+            }
+        }
+        kinds
+    }
+}
+
+impl Syntax {
+    /// 识别从 `index` 开始的标识符或数字
+    ///
+    /// # 返回
+    /// 单词的字素长度和类别; `index` 处不是单词开头时返回 `None`
+    fn word_at(&self, graphemes: &[&str], index: usize) -> Option<(usize, TokenKind)> {
+        let first = single_char(graphemes[index])?;
+        if !(first.is_alphanumeric() || first == '_') {
+            return None;
+        }
+        let len = graphemes[index..]
+            .iter()
+            .take_while(|g| single_char(g).is_some_and(|ch| ch.is_alphanumeric() || ch == '_'))
+            .count();
+        if first.is_ascii_digit() {
+            return Some((len, TokenKind::Number));
+        }
+        let word: String = graphemes[index..index + len].concat();
+        let kind = if self.keywords.contains(&word.as_str()) {
+            TokenKind::Keyword
+        } else {
+            TokenKind::Code
+        };
+        Some((len, kind))
+    }
+}
+
+/// 字素只由一个字符组成时返回该字符
+fn single_char(grapheme: &str) -> Option<char> {
+    let mut chars = grapheme.chars();
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+/// 字素是否恰好是字符 `ch`
+fn is_char(grapheme: &str, ch: char) -> bool {
+    single_char(grapheme) == Some(ch)
+}
+
+/// `token` 是否从 `index` 处开始出现
+fn matches_at(graphemes: &[&str], index: usize, token: &str) -> bool {
+    !token.is_empty()
+        && token.chars().enumerate().all(|(offset, ch)| {
+            graphemes
+                .get(index + offset)
+                .is_some_and(|g| is_char(g, ch))
+        })
+}
+
+/// 识别 `'x'` 或 `'\n'` 形式的字符字面量, 返回其字素长度
+fn char_literal_len(graphemes: &[&str], index: usize) -> Option<usize> {
+    if !is_char(graphemes[index], '\'') {
+        return None;
+    }
+    if graphemes.get(index + 1).is_some_and(|g| is_char(g, '\\')) {
+        // 转义形式, 向后寻找闭合引号
+        return graphemes[index + 2..]
+            .iter()
+            .skip(1)
+            .position(|g| is_char(g, '\''))
+            .map(|pos| pos + 4);
+    }
+    graphemes
+        .get(index + 2)
+        .is_some_and(|g| is_char(g, '\''))
+        .then_some(3)
+}
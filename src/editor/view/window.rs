@@ -0,0 +1,348 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 20:26:37
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 20:26:37
+ * @FilePath: \rim\src\editor\view\window.rs
+ * @Description: 窗口布局树与非焦点窗口状态
+ */
+//! 窗口模块
+//!
+//! - [`WindowState`] - 非焦点窗口显示的缓冲区、光标和滚动位置
+//! - [`WindowTree`] - 窗口布局树, 每次分割把一个窗口的区域上下或左右一分为二
+//!
+//! 窗口大小不单独保存, 每次都由窗口区尺寸和分割比例计算, 终端尺寸变化后自动适应
+
+use super::layout::Rect;
+use super::location::Location;
+use super::GraphemeLocation;
+use crate::editor::editorcommand::Direction;
+use crate::editor::terminal::Position;
+use std::mem;
+
+/// 每个窗口至少保留的行数(一行文本和状态行)
+pub const MIN_WINDOW_HEIGHT: usize = 2;
+
+/// 每个窗口至少保留的列数
+pub const MIN_WINDOW_WIDTH: usize = 2;
+
+/// 分割方向
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// 上下排列
+    Horizontal,
+    /// 左右排列, 中间有一列分隔线
+    Vertical,
+}
+
+/// 非焦点窗口的状态
+///
+/// 焦点窗口的状态保存在视图自身的字段中
+pub struct WindowState {
+    /// 显示的缓冲区下标
+    pub buffer: usize,
+    /// 光标的字素位置
+    pub grapheme_location: GraphemeLocation,
+    /// 滚动偏移
+    pub scroll_offset: Location,
+}
+
+/// 窗口布局树
+pub enum WindowTree {
+    /// 一个窗口, 值为窗口下标
+    Leaf(usize),
+    /// 一次分割
+    Split {
+        axis: Axis,
+        /// 两部分的大小比例
+        weights: [usize; 2],
+        /// 上(左)和下(右)两部分
+        children: Box<[WindowTree; 2]>,
+    },
+}
+
+impl Default for WindowTree {
+    fn default() -> Self {
+        Self::Leaf(0)
+    }
+}
+
+impl WindowTree {
+    /// 是否有多个窗口
+    pub fn is_split(&self) -> bool {
+        matches!(self, Self::Split { .. })
+    }
+
+    /// 计算每个窗口的区域和分隔线
+    ///
+    /// # 返回
+    /// 按从上到下、从左到右顺序排列的 `(窗口下标, 区域)`, 以及左右分割之间的分隔线区域
+    pub fn layout(&self, rect: Rect) -> (Vec<(usize, Rect)>, Vec<Rect>) {
+        let mut windows = Vec::new();
+        let mut separators = Vec::new();
+        self.collect(rect, &mut windows, &mut separators);
+        (windows, separators)
+    }
+
+    fn collect(&self, rect: Rect, windows: &mut Vec<(usize, Rect)>, separators: &mut Vec<Rect>) {
+        match self {
+            Self::Leaf(id) => windows.push((*id, rect)),
+            Self::Split {
+                axis,
+                weights,
+                children,
+            } => {
+                let [first, second] = split_rect(*axis, *weights, rect);
+                if *axis == Axis::Vertical && rect.width > 0 {
+                    separators.push(Rect {
+                        x: first.x + first.width,
+                        width: 1,
+                        ..rect
+                    });
+                }
+                children[0].collect(first, windows, separators);
+                children[1].collect(second, windows, separators);
+            }
+        }
+    }
+
+    /// 把窗口 `target` 一分为二, 新窗口 `new` 位于上方(左侧)
+    ///
+    /// # 返回
+    /// 未找到 `target` 时返回 `false`
+    pub fn split(&mut self, target: usize, new: usize, axis: Axis) -> bool {
+        match self {
+            Self::Leaf(id) if *id == target => {
+                *self = Self::Split {
+                    axis,
+                    weights: [1, 1],
+                    children: Box::new([Self::Leaf(new), Self::Leaf(target)]),
+                };
+                true
+            }
+            Self::Leaf(_) => false,
+            Self::Split { children, .. } => children
+                .iter_mut()
+                .any(|child| child.split(target, new, axis)),
+        }
+    }
+
+    /// 移除窗口 `target`, 它的区域并入同一分割的另一部分
+    ///
+    /// # 返回
+    /// 接管区域的第一个窗口, 未找到 `target` 或它是唯一的窗口时返回 `None`
+    pub fn remove(&mut self, target: usize) -> Option<usize> {
+        let Self::Split { children, .. } = self else {
+            return None;
+        };
+        let position = children
+            .iter()
+            .position(|child| matches!(child, Self::Leaf(id) if *id == target));
+        if let Some(position) = position {
+            let sibling = mem::take(&mut children[1 - position]);
+            *self = sibling;
+            return Some(self.first_window());
+        }
+        children.iter_mut().find_map(|child| child.remove(target))
+    }
+
+    /// 窗口被删除后, 把下标大于 `removed` 的窗口下标减一
+    pub fn renumber(&mut self, removed: usize) {
+        match self {
+            Self::Leaf(id) => {
+                if *id > removed {
+                    *id -= 1;
+                }
+            }
+            Self::Split { children, .. } => {
+                for child in children.iter_mut() {
+                    child.renumber(removed);
+                }
+            }
+        }
+    }
+
+    /// 调整窗口大小
+    ///
+    /// 调整包含该窗口的最内层同方向分割, 每次一行(列), 另一侧至少保留最小尺寸
+    ///
+    /// # 参数
+    /// - `target`: 窗口下标
+    /// - `axis`: 调整高度时为 [`Axis::Horizontal`], 调整宽度时为 [`Axis::Vertical`]
+    /// - `grow`: `true` 增大, `false` 减小
+    /// - `rect`: 本子树的区域
+    ///
+    /// # 返回
+    /// 没有同方向的分割或已到最小尺寸时返回 `false`
+    pub fn resize(&mut self, target: usize, axis: Axis, grow: bool, rect: Rect) -> bool {
+        let Self::Split {
+            axis: split_axis,
+            weights,
+            children,
+        } = self
+        else {
+            return false;
+        };
+        let rects = split_rect(*split_axis, *weights, rect);
+        let Some(index) = children.iter().position(|child| child.contains(target)) else {
+            return false;
+        };
+        if children[index].resize(target, axis, grow, rects[index]) {
+            return true;
+        }
+        if *split_axis != axis {
+            return false;
+        }
+        let (mut sizes, minimum) = match axis {
+            Axis::Horizontal => ([rects[0].height, rects[1].height], MIN_WINDOW_HEIGHT),
+            Axis::Vertical => ([rects[0].width, rects[1].width], MIN_WINDOW_WIDTH),
+        };
+        let (grown, shrunk) = if grow {
+            (index, 1 - index)
+        } else {
+            (1 - index, index)
+        };
+        if sizes[shrunk] <= minimum {
+            return false;
+        }
+        sizes[grown] += 1;
+        sizes[shrunk] -= 1;
+        *weights = sizes;
+        true
+    }
+
+    /// 平分所有窗口
+    ///
+    /// 每个分割按两部分在该方向上排列的窗口数分配比例
+    pub fn equalize(&mut self) {
+        if let Self::Split {
+            axis,
+            weights,
+            children,
+        } = self
+        {
+            *weights = [children[0].span(*axis), children[1].span(*axis)];
+            for child in children.iter_mut() {
+                child.equalize();
+            }
+        }
+    }
+
+    /// 在指定方向上排列的窗口数
+    fn span(&self, axis: Axis) -> usize {
+        match self {
+            Self::Leaf(_) => 1,
+            Self::Split {
+                axis: split_axis,
+                children,
+                ..
+            } => {
+                let spans = [children[0].span(axis), children[1].span(axis)];
+                if *split_axis == axis {
+                    spans[0] + spans[1]
+                } else {
+                    spans[0].max(spans[1])
+                }
+            }
+        }
+    }
+
+    /// 是否包含窗口 `target`
+    fn contains(&self, target: usize) -> bool {
+        match self {
+            Self::Leaf(id) => *id == target,
+            Self::Split { children, .. } => children.iter().any(|child| child.contains(target)),
+        }
+    }
+
+    /// 最上(左)方的窗口
+    fn first_window(&self) -> usize {
+        match self {
+            Self::Leaf(id) => *id,
+            Self::Split { children, .. } => children[0].first_window(),
+        }
+    }
+}
+
+/// 查找指定方向上的相邻窗口
+///
+/// 优先选择最近的窗口, 距离相同时选择与光标所在行(列)重叠或最接近的窗口
+///
+/// # 参数
+/// - `windows`: [`WindowTree::layout`] 计算的窗口区域
+/// - `from`: 当前窗口下标
+/// - `cursor`: 光标的屏幕位置
+/// - `direction`: 方向, 只支持上下左右
+pub fn neighbor(
+    windows: &[(usize, Rect)],
+    from: usize,
+    cursor: Position,
+    direction: Direction,
+) -> Option<usize> {
+    let current = windows.iter().find(|(id, _)| *id == from)?.1;
+    windows
+        .iter()
+        .filter(|(id, _)| *id != from)
+        .filter_map(|(id, rect)| {
+            let distance = match direction {
+                Direction::Left => current.x.checked_sub(rect.x + rect.width),
+                Direction::Right => rect.x.checked_sub(current.x + current.width),
+                Direction::Up => current.y.checked_sub(rect.y + rect.height),
+                Direction::Down => rect.y.checked_sub(current.y + current.height),
+                _ => None,
+            }?;
+            let offset = match direction {
+                Direction::Left | Direction::Right => gap(cursor.y, rect.y, rect.height),
+                _ => gap(cursor.x, rect.x, rect.width),
+            };
+            Some(((distance, offset), *id))
+        })
+        .min()
+        .map(|(_, id)| id)
+}
+
+/// 按比例把区域分给两部分, 左右分割时中间留一列分隔线
+fn split_rect(axis: Axis, weights: [usize; 2], rect: Rect) -> [Rect; 2] {
+    let total = match axis {
+        Axis::Horizontal => rect.height,
+        Axis::Vertical => rect.width.saturating_sub(1),
+    };
+    let first = (total * weights[0])
+        .checked_div(weights[0] + weights[1])
+        .unwrap_or(0)
+        .min(total);
+    let second = total - first;
+    match axis {
+        Axis::Horizontal => [
+            Rect {
+                height: first,
+                ..rect
+            },
+            Rect {
+                y: rect.y + first,
+                height: second,
+                ..rect
+            },
+        ],
+        Axis::Vertical => [
+            Rect {
+                width: first,
+                ..rect
+            },
+            Rect {
+                x: rect.x + first + 1,
+                width: second,
+                ..rect
+            },
+        ],
+    }
+}
+
+/// 位置到区间 `[start, start + len)` 的距离
+fn gap(position: usize, start: usize, len: usize) -> usize {
+    if position < start {
+        start - position
+    } else {
+        position.saturating_sub((start + len).saturating_sub(1))
+    }
+}
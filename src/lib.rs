@@ -0,0 +1,24 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-18 13:41:09
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-18 13:41:09
+ * @FilePath: \rim\src\lib.rs
+ * @Description: 库入口, 供可执行文件和测试使用
+ */
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::print_stdout,
+    clippy::as_conversions,
+    clippy::integer_division
+)]
+// 文档统一使用中文标题 `# 错误`
+#![allow(clippy::missing_errors_doc)]
+
+//! RIM 文本编辑器库
+//!
+//! 对外提供 [`editor::Editor`] 及终端后端, 测试可以通过
+//! [`editor::VirtualTerminal`] 在没有 TTY 的环境下驱动编辑器并检查绘制结果
+
+pub mod editor;
@@ -18,16 +18,40 @@
 //!
 //! 提供编辑器核心功能入口点，负责初始化并运行编辑器实例
 
-mod editor;
-use editor::Editor;
+use rim_viewer::editor::{Action, Args, Editor};
+use std::{env, process};
+
+const NAME: &str = env!("CARGO_PKG_NAME");
 
 /// 编辑器主入口函数
 ///
-/// 初始化默认编辑器实例并启动主事件循环
+/// 在终端进入 raw mode 之前解析命令行参数, 帮助、版本和错误信息直接打印后退出;
+/// 否则初始化编辑器实例并启动主事件循环
 ///
-/// # 错误
-/// 返回 `std::io::Error` 如果终端初始化失败
+/// 参数错误时退出码为 2, 编辑器初始化失败时为 1
+#[allow(clippy::print_stdout)]
 fn main() {
-    Editor::new().unwrap().run();
-    // Editor::default().run();
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(Action::Open(args)) => args,
+        Ok(Action::Help) => {
+            println!("{}", Args::usage());
+            return;
+        }
+        Ok(Action::Version) => {
+            println!("{}", Args::version());
+            return;
+        }
+        Err(err) => {
+            eprintln!("{NAME}: {err}");
+            eprintln!("Try '{NAME} --help' for more information.");
+            process::exit(2);
+        }
+    };
+    match Editor::new(&args) {
+        Ok(mut editor) => editor.run(),
+        Err(err) => {
+            eprintln!("{NAME}: {err}");
+            process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,6 @@
+fn main() {
+    let words = ["alpha", "beta", "gamma"];
+    for word in words {
+        println!("{word}");
+    }
+}
@@ -0,0 +1,505 @@
+/*
+ * @Author: iming 2576226012@qq.com
+ * @Date: 2026-10-19 09:12:40
+ * @LastEditors: iming 2576226012@qq.com
+ * @LastEditTime: 2026-10-19 09:12:40
+ * @FilePath: \rim\tests\snapshot.rs
+ * @Description: 基于虚拟终端的屏幕快照测试
+ */
+//! 屏幕快照测试
+//!
+//! 通过 [`Editor::with_backend`] 把编辑器接到 [`VirtualTerminal`] 上, 预先排队按键后运行到事件耗尽,
+//! 再比较屏幕上的文本
+//!
+//! 打开的文件位于 `tests/fixtures`, 使用相对路径使快照与机器无关;
+//! 配置、状态和缓存目录指向 `CARGO_TARGET_TMPDIR` 下每个测试各自的目录, 不读写用户主目录
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use rim_viewer::editor::{Args, Editor, FileArg, Size, VirtualTerminal};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// 测试各自的临时目录, 每次运行前清空
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+/// 不读写用户主目录的启动参数
+///
+/// # 参数
+/// - `name`: 测试名, 决定临时目录
+/// - `files`: 要打开的文件
+fn args(name: &str, files: &[&str]) -> Args {
+    let dir = scratch_dir(name);
+    Args {
+        files: files
+            .iter()
+            .map(|path| FileArg {
+                path: (*path).to_string(),
+                ..FileArg::default()
+            })
+            .collect(),
+        config_dir: Some(dir.join("config")),
+        state_dir: Some(dir.join("state")),
+        cache_dir: Some(dir.join("cache")),
+        ..Args::default()
+    }
+}
+
+/// 构建接在虚拟终端上的编辑器
+fn editor(args: &Args, size: Size) -> (Editor, VirtualTerminal) {
+    let terminal = VirtualTerminal::new(size);
+    let editor = Editor::with_backend(Box::new(terminal.clone()), args).expect("start editor");
+    (editor, terminal)
+}
+
+/// 期望的屏幕快照, 每行以 `\n` 结尾, 与 [`VirtualTerminal::snapshot`] 的格式相同
+fn screen(rows: &[&str]) -> String {
+    rows.iter().map(|row| format!("{row}\n")).collect()
+}
+
+/// 排队 `Ctrl+<ch>`
+fn push_ctrl(terminal: &VirtualTerminal, ch: char) {
+    terminal.push_key(KeyCode::Char(ch), KeyModifiers::CONTROL);
+}
+
+/// 等待文件查找器遍历完工作目录(标题中不再有 `...`)
+fn wait_for_finder(editor: &mut Editor, terminal: &VirtualTerminal) {
+    for _ in 0..500 {
+        editor.run();
+        if !terminal.row(0).contains("...") {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("file finder did not finish: {}", terminal.row(0));
+}
+
+#[test]
+fn startup_without_file() {
+    let (mut editor, terminal) = editor(
+        &args("startup_without_file", &[]),
+        Size {
+            height: 8,
+            width: 40,
+        },
+    );
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            "~",
+            "~",
+            "~  rim-viewer editor -- version 0.1.0",
+            "~",
+            "~Press <F1> for help; Press <Ctrl+q> to",
+            "~",
+            "~",
+            " [No Name]   text | LF | 1:1 / 0 (100%)",
+        ])
+    );
+}
+
+#[test]
+fn startup_with_file() {
+    let (mut editor, terminal) = editor(
+        &args("startup_with_file", &["tests/fixtures/sample.rs"]),
+        Size {
+            height: 12,
+            width: 50,
+        },
+    );
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            r#"[INFO] "tests/fixtures/sample.rs" opened (Rust)."#,
+            "",
+            "",
+            "",
+            "",
+            "fn main() {",
+            r#"    let words = ["alpha", "beta", "gamma"];"#,
+            "    for word in words {",
+            r#"        println!("{word}");"#,
+            "    }",
+            "}",
+            " …/sample.rs    Rust | LF | utf-8 | 1:1 / 6 (16%)",
+        ])
+    );
+}
+
+#[test]
+fn resize_redraws_at_new_size() {
+    let (mut editor, terminal) = editor(
+        &args("resize_redraws_at_new_size", &["tests/fixtures/sample.rs"]),
+        Size {
+            height: 12,
+            width: 50,
+        },
+    );
+    editor.run();
+    terminal.resize(Size {
+        height: 6,
+        width: 30,
+    });
+    terminal.push_event(Event::Resize(30, 6));
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            "fn main() {",
+            r#"    let words = ["alpha", "bet"#,
+            "    for word in words {",
+            r#"        println!("{word}");"#,
+            "    }",
+            " …/sample.rs    1:1 / 6 (16%)",
+        ])
+    );
+}
+
+#[test]
+fn toggle_info_panel() {
+    let (mut editor, terminal) = editor(
+        &args("toggle_info_panel", &["tests/fixtures/notes.txt"]),
+        Size {
+            height: 10,
+            width: 40,
+        },
+    );
+    push_ctrl(&terminal, 'l');
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            "first line",
+            "second line",
+            "third line",
+            "~",
+            "~",
+            "~",
+            "~",
+            "~",
+            "~",
+            " …/notes.txt  text | LF | 1:1 / 3 (33%)",
+        ])
+    );
+}
+
+#[test]
+fn buffer_picker() {
+    let (mut editor, terminal) = editor(
+        &args(
+            "buffer_picker",
+            &["tests/fixtures/sample.rs", "tests/fixtures/notes.txt"],
+        ),
+        Size {
+            height: 8,
+            width: 50,
+        },
+    );
+    push_ctrl(&terminal, 'b');
+    terminal.push_keys("j");
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            " Buffers (2)    <Enter> open  <d> close buffer  <q",
+            "  1 %   tests/fixtures/sample.rs  (6 lines)",
+            "  2     tests/fixtures/notes.txt  (3 lines)",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ])
+    );
+}
+
+#[test]
+fn split_windows() {
+    let (mut editor, terminal) = editor(
+        &args(
+            "split_windows",
+            &["tests/fixtures/sample.rs", "tests/fixtures/notes.txt"],
+        ),
+        Size {
+            height: 14,
+            width: 60,
+        },
+    );
+    push_ctrl(&terminal, 'l');
+    push_ctrl(&terminal, 'w');
+    terminal.push_keys("s");
+    push_ctrl(&terminal, 'w');
+    terminal.push_keys("v");
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[
+            "fn main() {                  │fn main() {",
+            r#"    let words = ["alpha", "be│    let words = ["alpha", "bet"#,
+            "    for word in words {      │    for word in words {",
+            r#"        println!("{word}");  │        println!("{word}");"#,
+            "    }                        │    }",
+            "}                            │}",
+            " …/sample.rs   1:1 / 6 (16%) │ …/sample.rs    1:1 / 6 (16%)",
+            "fn main() {",
+            r#"    let words = ["alpha", "beta", "gamma"];"#,
+            "    for word in words {",
+            r#"        println!("{word}");"#,
+            "    }",
+            "}",
+            " tests/fixtures/sample.rs Rust | LF | utf-8 | 1:1 / 6 (16%)",
+        ])
+    );
+}
+
+#[test]
+fn buffer_picker_on_one_row_terminal() {
+    let (mut editor, terminal) = editor(
+        &args(
+            "buffer_picker_on_one_row_terminal",
+            &["tests/fixtures/sample.rs", "tests/fixtures/notes.txt"],
+        ),
+        Size {
+            height: 1,
+            width: 40,
+        },
+    );
+    push_ctrl(&terminal, 'b');
+    terminal.push_keys("j");
+    editor.run();
+    assert_eq!(
+        terminal.snapshot(),
+        screen(&[" Buffers (2)    <Enter> open  <d> close"])
+    );
+    assert_eq!(terminal.cursor_position().y, 0);
+}
+
+#[test]
+fn opening_a_file_stops_follow() {
+    let (mut editor, terminal) = editor(
+        &args("opening_a_file_stops_follow", &["tests/fixtures/sample.rs"]),
+        Size {
+            height: 12,
+            width: 60,
+        },
+    );
+    terminal.push_keys("F");
+    push_ctrl(&terminal, 'p');
+    terminal.push_keys("fixtures/notes");
+    wait_for_finder(&mut editor, &terminal);
+    terminal.push_key(KeyCode::Enter, KeyModifiers::NONE);
+    editor.run();
+    let info = (0..5).map(|row| terminal.row(row)).collect::<Vec<_>>();
+    assert_eq!(
+        info,
+        [
+            r#"[INFO] "tests/fixtures/sample.rs" opened (Rust)."#,
+            "[INFO] Following tests/fixtures/sample.rs, move or press <F>",
+            "[INFO] Stopped following.",
+            r#"[INFO] "tests/fixtures/notes.txt" opened."#,
+            "",
+        ]
+    );
+}
+
+#[test]
+fn same_file_twice_shares_no_swap_file() {
+    let (mut editor, terminal) = editor(
+        &args(
+            "same_file_twice_shares_no_swap_file",
+            &["tests/fixtures/notes.txt", "tests/fixtures/notes.txt"],
+        ),
+        Size {
+            height: 12,
+            width: 100,
+        },
+    );
+    editor.run();
+    assert_eq!(
+        terminal.row(2),
+        r#"[INFO] "tests/fixtures/notes.txt" is already open in another buffer, no swap file will be written."#
+    );
+}
+
+#[test]
+fn view_state_keeps_search_history_lines() {
+    let args = args(
+        "view_state_keeps_search_history_lines",
+        &["tests/fixtures/notes.txt"],
+    );
+    let state_dir = args.state_dir.clone().expect("state dir");
+    fs::create_dir_all(&state_dir).expect("create state dir");
+    let state_file = state_dir.join("viewstate");
+    fs::write(&state_file, "# rim view state\n/older\n/newer\n").expect("write view state");
+    let (mut editor, terminal) = editor(
+        &args,
+        Size {
+            height: 10,
+            width: 40,
+        },
+    );
+    terminal.push_keys("j");
+    editor.run();
+    let contents = fs::read_to_string(&state_file).expect("read view state");
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines[..3], ["# rim view state", "/older", "/newer"]);
+    assert!(lines[3].starts_with("1 0 0 0 -\t"));
+    assert!(lines[3].ends_with("notes.txt"));
+}
+
+#[test]
+fn read_only_directory_browser() {
+    let mut args = args("read_only_directory_browser", &[]);
+    let dir = args.state_dir.clone().expect("state dir");
+    let files = dir.parent().expect("scratch dir").join("files");
+    fs::create_dir_all(&files).expect("create files dir");
+    let victim = files.join("victim.txt");
+    fs::write(&victim, "keep me\n").expect("write file");
+    args.files = vec![FileArg {
+        path: files.to_string_lossy().into_owned(),
+        ..FileArg::default()
+    }];
+    args.read_only = true;
+    let (mut editor, terminal) = editor(
+        &args,
+        Size {
+            height: 12,
+            width: 80,
+        },
+    );
+    terminal.push_keys("jdy");
+    terminal.push_key(KeyCode::Enter, KeyModifiers::NONE);
+    terminal.push_paste("changed ");
+    editor.run();
+    assert_eq!(fs::read_to_string(&victim).expect("file kept"), "keep me\n");
+    let info: Vec<String> = (0..5).map(|row| terminal.row(row)).collect();
+    assert_eq!(info[1], "[INFO] Read-only mode, files cannot be changed.");
+    assert_eq!(info[4], "[EDIT] Buffer is read-only, paste ignored.");
+    assert_eq!(terminal.row(5), "keep me");
+}
+
+#[test]
+fn read_only_file_from_finder() {
+    let mut args = args("read_only_file_from_finder", &["tests/fixtures/sample.rs"]);
+    args.pager = true;
+    args.read_only = true;
+    let (mut editor, terminal) = editor(
+        &args,
+        Size {
+            height: 12,
+            width: 60,
+        },
+    );
+    push_ctrl(&terminal, 'p');
+    terminal.push_keys("fixtures/notes");
+    wait_for_finder(&mut editor, &terminal);
+    terminal.push_key(KeyCode::Enter, KeyModifiers::NONE);
+    terminal.push_paste("changed ");
+    editor.run();
+    assert_eq!(
+        terminal.row(2),
+        "[EDIT] Buffer is read-only, paste ignored."
+    );
+    assert_eq!(terminal.row(5), "first line");
+}
+
+#[test]
+fn paste_counts_normalized_characters() {
+    let (mut editor, terminal) = editor(
+        &args("paste_counts_normalized_characters", &[]),
+        Size {
+            height: 10,
+            width: 40,
+        },
+    );
+    terminal.push_paste("X\r\nY\rZ");
+    editor.run();
+    let rows: Vec<String> = (0..10).map(|row| terminal.row(row)).collect();
+    assert!(
+        rows.iter()
+            .any(|row| row == "[EDIT] Pasted 5 chars, 3 lines"),
+        "{rows:#?}"
+    );
+}
+
+/// 松开 `a` 键的事件
+fn key_release() -> Event {
+    Event::Key(KeyEvent::new_with_kind(
+        KeyCode::Char('a'),
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+    ))
+}
+
+#[test]
+fn key_release_is_not_logged_by_default() {
+    let (mut editor, terminal) = editor(
+        &args("key_release_is_not_logged_by_default", &[]),
+        Size {
+            height: 10,
+            width: 60,
+        },
+    );
+    terminal.push_event(key_release());
+    editor.run();
+    assert!((0..5).all(|row| !terminal.row(row).starts_with("[KEY")));
+}
+
+#[test]
+fn key_release_is_logged_when_configured() {
+    let args = args("key_release_is_logged_when_configured", &[]);
+    let config_dir = args.config_dir.clone().expect("config dir");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    fs::write(config_dir.join("config"), "log_key_release = true\n").expect("write config");
+    let (mut editor, terminal) = editor(
+        &args,
+        Size {
+            height: 10,
+            width: 60,
+        },
+    );
+    terminal.push_event(key_release());
+    editor.run();
+    assert!(
+        (0..5).any(|row| terminal.row(row).starts_with("[KEY ] Release: code=a")),
+        "{}",
+        terminal.snapshot()
+    );
+}
+
+#[test]
+fn help_lists_key_bindings() {
+    let (mut editor, terminal) = editor(
+        &args("help_lists_key_bindings", &[]),
+        Size {
+            height: 6,
+            width: 70,
+        },
+    );
+    terminal.push_key(KeyCode::F(1), KeyModifiers::NONE);
+    terminal.push_keys("j");
+    editor.run();
+    let help = terminal.snapshot();
+    terminal.push_keys("q");
+    editor.run();
+    assert_eq!(
+        help,
+        screen(&[
+            " Key bindings 6/32    <j>/<k> scroll  <q> close",
+            " Ctrl+q                Quit",
+            " Ctrl+z                Suspend to the shell, resume with fg",
+            " h j k l / arrows      Move the cursor",
+            " PgUp / PgDn           Move one page up / down",
+            " Home / End            Move to the start / end of the line",
+        ])
+    );
+    assert!(!terminal.snapshot().contains("Key bindings"));
+}